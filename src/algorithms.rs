@@ -1,19 +1,100 @@
 //! various algorithms on graphs
 
-use std::collections::VecDeque;
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashSet, VecDeque},
+};
 
 use crate::{
     graph::{Graph, Handle},
     unweighted::Unweighted,
-    HasEdge,
+    util::DisjointSet,
+    CycleError, HasEdge,
 };
 
+mod unweighted;
+mod weighted;
+
+/// The order in which [Graph::dfs_order] returns the vertices visited by a depth-first search
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DfsOrder {
+    /// Every vertex comes before the vertices discovered from it
+    PreOrder,
+    /// Every vertex comes after all vertices discovered from it
+    PostOrder,
+}
+
+/// Entry of a priority queue ordered so that the [BinaryHeap] pops the smallest vertex according to the comparator first
+struct ComparedBy<'a, F>(Handle, &'a F);
+
+impl<F: Fn(Handle, Handle) -> Ordering> PartialEq for ComparedBy<'_, F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<F: Fn(Handle, Handle) -> Ordering> Eq for ComparedBy<'_, F> {}
+
+impl<F: Fn(Handle, Handle) -> Ordering> PartialOrd for ComparedBy<'_, F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F: Fn(Handle, Handle) -> Ordering> Ord for ComparedBy<'_, F> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.1)(other.0, self.0)
+    }
+}
+
 impl<V, E> Graph<V, E>
 where
     Graph<V, E>: HasEdge,
 {
+    /// Returns the neighbors of every vertex, ignoring the direction of edges.
+    /// An edge `(u, v)` makes `v` a neighbor of `u` and `u` a neighbor of `v`
+    pub(crate) fn undirected_neighbors(&self) -> Vec<Vec<usize>> {
+        let mut neighbors = vec![vec![]; self.size()];
+        for from in 0..self.size() {
+            for Handle(to) in self.connected_neighbors(Handle(from)) {
+                neighbors[from].push(to);
+                if from != to {
+                    neighbors[to].push(from);
+                }
+            }
+        }
+        neighbors
+    }
+
+    /// Like [Graph::undirected_neighbors] but without self loops and with every neighbor
+    /// appearing only once, i.e. the neighbors in the underlying simple undirected graph
+    pub(crate) fn simple_undirected_neighbors(&self) -> Vec<Vec<usize>> {
+        let mut neighbors = self.undirected_neighbors();
+        for (vertex, neighbors) in neighbors.iter_mut().enumerate() {
+            neighbors.sort_unstable();
+            neighbors.dedup();
+            neighbors.retain(|&neighbor| neighbor != vertex);
+        }
+        neighbors
+    }
+
+    /// Returns an iterator over the neighbors of `vertex`, yielding every neighbor only once
+    /// even if there are multiple edges from `vertex` to it
+    pub fn unique_neighbors(&self, vertex: Handle) -> impl Iterator<Item = Handle> + '_ {
+        let mut seen = HashSet::new();
+        self.connected_neighbors(vertex)
+            .filter(move |&neighbor| seen.insert(neighbor))
+    }
+
     /// Returns whether the given graph is connected.
     ///
+    /// The graph is connected if every vertex can be reached from every other vertex when ignoring the direction of edges.
+    ///
+    /// Every vertex is first assigned to the component of vertices reachable from it using an iterative depth-first search.
+    /// Edges between these components are then collected into a small component graph, which is searched iteratively as well.
+    /// Apart from the graph itself, this uses `O(n + c)` memory where `c` is the number of edges between different
+    /// components, and no recursion, so it works for arbitrarily large graphs.
+    ///
     /// ## Running Time
     /// This algorithm has a running time of `O(n + m)` where `n` is the number of vertices and `m` is the number of edges
     pub fn is_connected(&self) -> bool {
@@ -22,45 +103,51 @@ where
         }
 
         let mut zhk = vec![None; self.vertices.len()];
-        let mut current_zhk = 1usize; // can be NonZeroUsize
-        let mut zhk_connections = vec![];
+        // the components that can be reached from each other, ignoring the direction of edges
+        let mut zhk_graph: Vec<Vec<usize>> = vec![];
+        let mut stack = vec![];
 
         for i in 0..self.vertices.len() {
-            if zhk[i].is_none() {
-                let mut stack = vec![i];
-                let mut connections = vec![];
-
-                while let Some(top) = stack.pop() {
-                    zhk[top] = Some(current_zhk);
-
-                    for Handle(neighbor) in self.connected_neighbors(Handle(top)) {
-                        match zhk[neighbor] {
-                            None => stack.push(neighbor),
-                            Some(z) if z == current_zhk => {}
-                            Some(z) => connections.push(z),
+            if zhk[i].is_some() {
+                continue;
+            }
+
+            let current_zhk = zhk_graph.len();
+            zhk_graph.push(vec![]);
+            zhk[i] = Some(current_zhk);
+            stack.push(i);
+            while let Some(top) = stack.pop() {
+                for Handle(neighbor) in self.connected_neighbors(Handle(top)) {
+                    match zhk[neighbor] {
+                        None => {
+                            zhk[neighbor] = Some(current_zhk);
+                            stack.push(neighbor);
+                        }
+                        Some(z) if z == current_zhk => {}
+                        Some(z) => {
+                            if zhk_graph[current_zhk].last() != Some(&z) {
+                                zhk_graph[current_zhk].push(z);
+                                zhk_graph[z].push(current_zhk);
+                            }
                         }
                     }
                 }
-                eprintln!("{current_zhk} {connections:?}");
+            }
 
-                zhk_connections.push(connections);
-                current_zhk += 1;
+            // everything is reachable from the first vertex
+            if i == 0 && zhk.iter().all(Option::is_some) {
+                return true;
             }
         }
 
-        let mut zhk_graph = (1..current_zhk).collect::<Unweighted<_>>();
-        zhk_graph.construct_edges_from(|&a, &b| {
-            zhk_connections[b - 1].iter().any(|&elem| elem == a)
-                || zhk_connections[a - 1].iter().any(|&elem| elem == b)
-        });
-
-        let mut seen = vec![false; zhk_graph.size()];
+        let mut seen = vec![false; zhk_graph.len()];
+        seen[0] = true;
         let mut stack = vec![0];
         while let Some(top) = stack.pop() {
-            seen[top] = true;
-            for Handle(neighbor) in zhk_graph.connected_neighbors(Handle(top)) {
+            for &neighbor in &zhk_graph[top] {
                 if !seen[neighbor] {
-                    stack.push(neighbor)
+                    seen[neighbor] = true;
+                    stack.push(neighbor);
                 }
             }
         }
@@ -165,124 +252,1440 @@ where
 
         seen[end]
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::{
-        graph::{Graph, Handle},
-        unweighted::Unweighted,
-        weighted::Weighted,
-    };
+    /// Returns the number of edges on a shortest path from `start` to every vertex, indexed by handle,
+    /// or `None` for vertices that are not reachable from `start`.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n + m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn bfs_distances(&self, start: Handle) -> Vec<Option<usize>> {
+        self.multi_source_bfs(&[start])
+    }
 
-    #[test]
-    fn is_connected() {
-        let mut graph: Weighted<_, f32> = ('a'..='f').collect();
-        graph.construct_edges_from(|&from, &to| match (from, to) {
-            ('a', 'b') => Some(9.0),
-            ('a', 'd') => Some(8.0),
-            ('b', 'c') => Some(1.0),
-            ('b', 'e') => Some(3.0),
-            ('c', 'e') => Some(1.0),
-            ('c', 'd') => Some(5.0),
-            ('d', 'f') => Some(8.0),
-            ('e', 'f') => Some(6.0),
-            _ => None,
-        });
+    /// Returns the number of edges on a shortest path from any of the `sources` to every vertex, indexed by handle,
+    /// or `None` for vertices that are not reachable from any source. Every source has distance `0`.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n + m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn multi_source_bfs(&self, sources: &[Handle]) -> Vec<Option<usize>> {
+        let mut distances = vec![None; self.size()];
+        let mut queue = VecDeque::with_capacity(sources.len());
+        for &Handle(source) in sources {
+            if distances[source].is_none() {
+                distances[source] = Some(0);
+                queue.push_back(source);
+            }
+        }
 
-        assert!(graph.is_connected());
+        while let Some(front) = queue.pop_front() {
+            let next = distances[front].map(|distance| distance + 1);
+            for Handle(neighbor) in self.connected_neighbors(Handle(front)) {
+                if distances[neighbor].is_none() {
+                    distances[neighbor] = next;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
 
-        graph.remove_edge(
-            graph.get_vertex('a').unwrap(),
-            graph.get_vertex('b').unwrap(),
-        );
+        distances
+    }
 
-        graph.remove_edge(
-            graph.get_vertex('a').unwrap(),
-            graph.get_vertex('d').unwrap(),
+    /// Appends the vertices reachable from `start` and not yet `seen` to `out` in the given depth-first `order`
+    pub(crate) fn dfs_from(
+        &self,
+        start: usize,
+        seen: &mut [bool],
+        order: DfsOrder,
+        out: &mut Vec<usize>,
+    ) {
+        if seen[start] {
+            return;
+        }
+        seen[start] = true;
+        if order == DfsOrder::PreOrder {
+            out.push(start);
+        }
+
+        let mut stack = vec![(start, self.connected_neighbors(Handle(start)))];
+        while let Some((vertex, neighbors)) = stack.last_mut() {
+            match neighbors.next() {
+                Some(Handle(neighbor)) if !seen[neighbor] => {
+                    seen[neighbor] = true;
+                    if order == DfsOrder::PreOrder {
+                        out.push(neighbor);
+                    }
+                    stack.push((neighbor, self.connected_neighbors(Handle(neighbor))));
+                }
+                Some(_) => {}
+                None => {
+                    if order == DfsOrder::PostOrder {
+                        out.push(*vertex);
+                    }
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    /// Returns the vertices reachable from `start` in the order in which a depth-first search visits them.
+    ///
+    /// Neighbors are visited in the order of [HasEdge::connected_neighbors].
+    /// See [DfsOrder] for the available orders.
+    ///
+    /// ```
+    /// # use comtesse::{unweighted::Unweighted, DfsOrder};
+    /// let mut graph: Unweighted<_> = (0..3).collect();
+    /// graph.construct_edges_from(|&u, &v| u == 0 && v != 0);
+    /// let root = graph.get_vertex(0).unwrap();
+    ///
+    /// let preorder = graph.dfs_order(root, DfsOrder::PreOrder);
+    /// let postorder = graph.dfs_order(root, DfsOrder::PostOrder);
+    /// assert_eq!(preorder.first(), Some(&root));
+    /// assert_eq!(postorder.last(), Some(&root));
+    /// ```
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n + m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn dfs_order(&self, start: Handle, order: DfsOrder) -> Vec<Handle> {
+        let mut out = vec![];
+        self.dfs_from(start.0, &mut vec![false; self.size()], order, &mut out);
+        out.into_iter().map(Handle).collect()
+    }
+
+    /// Computes the immediate dominator of every vertex with respect to `entry`.
+    ///
+    /// A vertex `d` dominates `v` if every path from `entry` to `v` passes through `d`.
+    /// The immediate dominator of `v` is the unique dominator of `v` closest to it (other than `v` itself).
+    /// The immediate dominator of `entry` and of every vertex not reachable from `entry` is `None`.
+    ///
+    /// This uses the iterative algorithm by Cooper, Harvey and Kennedy.
+    ///
+    /// ```
+    /// # use comtesse::unweighted::Unweighted;
+    /// let mut graph: Unweighted<_> = (0..4).collect();
+    /// graph.construct_edges_from(|&u, &v| matches!((u, v), (0, 1) | (0, 2) | (1, 3) | (2, 3)));
+    /// let handles: Vec<_> = (0..4).map(|i| graph.get_vertex(i).unwrap()).collect();
+    ///
+    /// let idom = graph.dominators(handles[0]);
+    /// assert_eq!(idom, [None, Some(handles[0]), Some(handles[0]), Some(handles[0])]);
+    /// ```
+    ///
+    /// ## Running Time
+    /// This algorithm has a worst case running time of `O(n * m)` where `n` is the number of vertices and `m` is the number of edges,
+    /// but is close to linear for typical control flow graphs
+    pub fn dominators(&self, entry: Handle) -> Vec<Option<Handle>> {
+        let mut postorder = Vec::with_capacity(self.size());
+        self.dfs_from(
+            entry.0,
+            &mut vec![false; self.size()],
+            DfsOrder::PostOrder,
+            &mut postorder,
         );
 
-        assert!(!graph.is_connected());
+        let mut order = vec![None; self.size()];
+        for (i, &vertex) in postorder.iter().enumerate() {
+            order[vertex] = Some(i);
+        }
+
+        let mut predecessors = vec![vec![]; self.size()];
+        for &from in &postorder {
+            for Handle(to) in self.connected_neighbors(Handle(from)) {
+                predecessors[to].push(from);
+            }
+        }
+
+        let intersect = |idom: &[Option<usize>], mut a: usize, mut b: usize| {
+            while a != b {
+                while order[a] < order[b] {
+                    a = idom[a].expect("processed vertices have a dominator");
+                }
+                while order[b] < order[a] {
+                    b = idom[b].expect("processed vertices have a dominator");
+                }
+            }
+            a
+        };
+
+        let mut idom = vec![None; self.size()];
+        idom[entry.0] = Some(entry.0);
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &vertex in postorder.iter().rev().skip(1) {
+                let new_idom = predecessors[vertex]
+                    .iter()
+                    .filter(|&&pred| idom[pred].is_some())
+                    .fold(None, |new_idom, &pred| match new_idom {
+                        None => Some(pred),
+                        Some(new_idom) => Some(intersect(&idom, pred, new_idom)),
+                    });
+
+                if new_idom != idom[vertex] {
+                    idom[vertex] = new_idom;
+                    changed = true;
+                }
+            }
+        }
+
+        idom[entry.0] = None;
+        idom.into_iter().map(|dom| dom.map(Handle)).collect()
     }
 
-    #[test]
-    fn connected1() {
-        let mut graph: Unweighted<_> = ('a'..='c').collect();
-        graph.construct_edges_from(|&from, &to| matches!((from, to), ('b', 'a') | ('c', 'a')));
-        assert!(graph.is_connected());
+    /// Returns which vertices are reachable from `vertex` by following directed edges.
+    /// The result is indexed by handle and `vertex` is always reachable from itself.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n + m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn reachable_from(&self, vertex: Handle) -> Vec<bool> {
+        let mut seen = vec![false; self.size()];
+        seen[vertex.0] = true;
+        let mut stack = vec![vertex.0];
+
+        while let Some(top) = stack.pop() {
+            for Handle(neighbor) in self.connected_neighbors(Handle(top)) {
+                if !seen[neighbor] {
+                    seen[neighbor] = true;
+                    stack.push(neighbor);
+                }
+            }
+        }
+
+        seen
     }
 
-    fn assert_one_of_paths<V, E>(
-        graph: &Graph<V, E>,
-        actual: Option<Vec<Handle>>,
-        expected: &[&[V]],
-    ) where
-        V: Eq + Clone,
-    {
-        if expected.is_empty() {
-            return;
+    /// Returns whether there is a directed path from `from` to `to`.
+    ///
+    /// This is equivalent to `shortest_path_unweighted(from, to).is_some()`, but the search stops
+    /// as soon as `to` is found and no path is reconstructed.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n + m)` where `n` is the number of vertices and `m` is the number of edges
+    #[doc(alias = "is_reachable")]
+    pub fn can_reach(&self, from: Handle, to: Handle) -> bool {
+        if from == to {
+            return true;
         }
 
-        let actual = actual.unwrap();
+        let mut seen = vec![false; self.size()];
+        seen[from.0] = true;
+        let mut queue = VecDeque::new();
+        queue.push_back(from.0);
 
-        for &expected in expected {
-            let expected = expected
-                .iter()
-                .map(|i| graph.get_vertex(i.clone()).unwrap())
-                .collect::<Vec<_>>();
-            if actual == expected {
-                return;
+        while let Some(front) = queue.pop_front() {
+            for Handle(neighbor) in self.connected_neighbors(Handle(front)) {
+                if neighbor == to.0 {
+                    return true;
+                }
+                if !seen[neighbor] {
+                    seen[neighbor] = true;
+                    queue.push_back(neighbor);
+                }
             }
         }
 
-        panic!("no path matched with {actual:?}");
+        false
     }
 
-    fn make_graph() -> Unweighted<char> {
-        let mut graph: Unweighted<_> = ('a'..='h').collect();
-        graph.construct_edges_from(|&u, &v| {
-            matches!(
-                (u, v),
-                ('f', 'd')
-                    | ('d', 'h')
-                    | ('c', 'g')
-                    | ('c', 'a')
-                    | ('b', 'f')
-                    | ('b', 'e')
-                    | ('a', 'b')
-                    | ('e', 'h')
-                    | ('d', 'g')
-                    | ('d', 'e')
-                    | ('e', 'c')
-            )
-        });
-        graph
+    /// Returns whether `path` is a walk in the graph, i.e. whether there is an edge from every vertex
+    /// of `path` to the next one. Paths with fewer than two vertices are always valid.
+    pub fn is_valid_path(&self, path: &[Handle]) -> bool {
+        path.windows(2).all(|pair| self.has_edge(pair[0], pair[1]))
     }
 
-    #[test]
-    fn shortest_path() {
-        let graph = make_graph();
+    /// Returns the strongly connected components of the graph.
+    ///
+    /// Two vertices are in the same strongly connected component if and only if
+    /// there is a directed path from each of them to the other. Every component is
+    /// sorted by handle and the components are returned in topological order, i.e.
+    /// edges between different components only go from earlier to later components.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n + m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn strongly_connected_components(&self) -> Vec<Vec<Handle>> {
+        let mut seen = vec![false; self.size()];
+        let mut postorder = Vec::with_capacity(self.size());
+        for vertex in 0..self.size() {
+            self.dfs_from(vertex, &mut seen, DfsOrder::PostOrder, &mut postorder);
+        }
 
-        let a = graph.get_vertex('a').unwrap();
-        let g = graph.get_vertex('g').unwrap();
-        let d = graph.get_vertex('d').unwrap();
+        let mut reversed = vec![vec![]; self.size()];
+        for from in 0..self.size() {
+            for Handle(to) in self.connected_neighbors(Handle(from)) {
+                reversed[to].push(from);
+            }
+        }
 
-        let path_ag = graph.shortest_path_unweighted(a, g);
-        assert_one_of_paths(
-            &graph,
-            path_ag,
-            &[&['a', 'b', 'e', 'c', 'g'], &['a', 'b', 'f', 'd', 'g']],
-        );
+        let mut assigned = vec![false; self.size()];
+        let mut components = vec![];
+        for &root in postorder.iter().rev() {
+            if assigned[root] {
+                continue;
+            }
 
-        assert_eq!(graph.shortest_distance_unweighted(a, g), Some(4));
+            let mut component = vec![];
+            let mut stack = vec![root];
+            assigned[root] = true;
+            while let Some(top) = stack.pop() {
+                component.push(Handle(top));
+                for &neighbor in &reversed[top] {
+                    if !assigned[neighbor] {
+                        assigned[neighbor] = true;
+                        stack.push(neighbor);
+                    }
+                }
+            }
 
-        let path_ad = graph.shortest_path_unweighted(a, d);
-        assert_eq!(
-            path_ad,
-            ['a', 'b', 'f', 'd']
+            component.sort_unstable();
+            components.push(component);
+        }
+
+        components
+    }
+
+    /// Returns the condensation of the graph, where every strongly connected component
+    /// is collapsed into a single vertex holding the handles of the component.
+    ///
+    /// There is an edge between two components if there is an edge between any of their vertices.
+    /// The condensation never contains a cycle and its vertices are in topological order
+    /// (see [Graph::strongly_connected_components]).
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n + m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn condensation(&self) -> Unweighted<Vec<Handle>> {
+        let components = self.strongly_connected_components();
+
+        let mut component_of = vec![0; self.size()];
+        for (i, component) in components.iter().enumerate() {
+            for &Handle(vertex) in component {
+                component_of[vertex] = i;
+            }
+        }
+
+        let mut edges = vec![vec![]; components.len()];
+        for (i, component) in components.iter().enumerate() {
+            let mut targets = component
                 .iter()
-                .map(|&i| graph.get_vertex(i))
-                .collect(),
+                .flat_map(|&vertex| self.connected_neighbors(vertex))
+                .map(|Handle(to)| component_of[to])
+                .filter(|&to| to != i)
+                .collect::<Vec<_>>();
+            targets.sort_unstable();
+            targets.dedup();
+            edges[i] = targets.into_iter().map(Handle).collect();
+        }
+
+        Unweighted {
+            vertices: components,
+            edges,
+        }
+    }
+
+    /// Returns the number of edges pointing to each vertex
+    pub(crate) fn in_degrees(&self) -> Vec<usize> {
+        let mut in_degrees = vec![0; self.size()];
+        for vertex in 0..self.size() {
+            for Handle(neighbor) in self.connected_neighbors(Handle(vertex)) {
+                in_degrees[neighbor] += 1;
+            }
+        }
+        in_degrees
+    }
+
+    /// Returns the vertices in topological order, so that every edge points from an earlier to a later vertex.
+    ///
+    /// Returns [CycleError] if the graph contains a cycle, since no such order exists then.
+    ///
+    /// ```
+    /// # use comtesse::unweighted::Unweighted;
+    /// let mut graph: Unweighted<_> = (1..=6).collect();
+    /// graph.construct_edges_from(|&u, &v| u != v && v % u == 0);
+    ///
+    /// let order = graph.topological_sort().unwrap();
+    /// assert_eq!(*graph.vertex_value(order[0]), 1);
+    /// ```
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n + m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn topological_sort(&self) -> Result<Vec<Handle>, CycleError> {
+        self.kahn(Vec::new(), Vec::push, Vec::pop)
+    }
+
+    /// Sorts the vertices topologically like [Graph::topological_sort], but whenever several vertices could come next,
+    /// the smallest of them according to `tie_break` is picked.
+    ///
+    /// Returns [CycleError] if the graph contains a cycle.
+    ///
+    /// ```
+    /// # use comtesse::unweighted::Unweighted;
+    /// let mut tasks: Unweighted<_> = ["test", "build", "deploy", "docs"].into_iter().collect();
+    /// tasks.construct_edges_from(|&u, &v| matches!((u, v), ("build", "test") | ("test", "deploy")));
+    ///
+    /// let order = tasks
+    ///     .topological_sort_by(|a, b| tasks.vertex_value(a).cmp(tasks.vertex_value(b)))
+    ///     .unwrap();
+    /// let order: Vec<_> = order.into_iter().map(|task| *tasks.vertex_value(task)).collect();
+    /// assert_eq!(order, ["build", "docs", "test", "deploy"]);
+    /// ```
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n log n + m)` where `n` is the number of vertices and `m` is the number of edges,
+    /// assuming that `tie_break` takes constant time
+    pub fn topological_sort_by<F>(&self, tie_break: F) -> Result<Vec<Handle>, CycleError>
+    where
+        F: Fn(Handle, Handle) -> Ordering,
+    {
+        self.kahn(
+            BinaryHeap::new(),
+            |ready, vertex| ready.push(ComparedBy(vertex, &tie_break)),
+            |ready| ready.pop().map(|ComparedBy(vertex, _)| vertex),
+        )
+    }
+
+    /// Kahn's algorithm, repeatedly taking the next vertex without remaining incoming edges out of `ready`.
+    /// The order in which `pop` returns the ready vertices determines which topological order is found
+    fn kahn<Q>(
+        &self,
+        mut ready: Q,
+        mut push: impl FnMut(&mut Q, Handle),
+        mut pop: impl FnMut(&mut Q) -> Option<Handle>,
+    ) -> Result<Vec<Handle>, CycleError> {
+        let mut in_degrees = self.in_degrees();
+        for vertex in (0..self.size()).rev() {
+            if in_degrees[vertex] == 0 {
+                push(&mut ready, Handle(vertex));
+            }
+        }
+
+        let mut order = Vec::with_capacity(self.size());
+        while let Some(top) = pop(&mut ready) {
+            order.push(top);
+            for neighbor in self.connected_neighbors(top) {
+                in_degrees[neighbor.0] -= 1;
+                if in_degrees[neighbor.0] == 0 {
+                    push(&mut ready, neighbor);
+                }
+            }
+        }
+
+        if order.len() == self.size() {
+            Ok(order)
+        } else {
+            Err(CycleError)
+        }
+    }
+
+    /// Returns whether the graph is a directed acyclic graph, i.e. whether it has no directed cycle.
+    ///
+    /// This is the precondition of [Graph::topological_sort], but does not store the order.
+    /// Self loops count as cycles.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n + m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn is_dag(&self) -> bool {
+        self.topological_sort().is_ok()
+    }
+
+    /// Returns the bridges of the graph, i.e. the edges whose removal disconnects their endpoints.
+    ///
+    /// Edges are treated as undirected and multiple edges between the same two vertices,
+    /// regardless of their direction, count as a single edge. Every bridge `(u, v)` is returned with `u < v`.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n + m log m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn bridges(&self) -> Vec<(Handle, Handle)> {
+        let neighbors = self.simple_undirected_neighbors();
+        let mut discovered = vec![None; self.size()];
+        let mut low = vec![0; self.size()];
+        let mut time = 0;
+        let mut bridges = vec![];
+
+        for root in 0..self.size() {
+            if discovered[root].is_some() {
+                continue;
+            }
+            discovered[root] = Some(time);
+            low[root] = time;
+            time += 1;
+
+            // (vertex, parent, index of the next neighbor to visit)
+            let mut stack = vec![(root, None, 0)];
+            while let Some((vertex, parent, next)) = stack.last_mut() {
+                let (vertex, parent) = (*vertex, *parent);
+                if let Some(&neighbor) = neighbors[vertex].get(*next) {
+                    *next += 1;
+                    if Some(neighbor) == parent {
+                        continue;
+                    }
+                    match discovered[neighbor] {
+                        Some(discovered) => low[vertex] = low[vertex].min(discovered),
+                        None => {
+                            discovered[neighbor] = Some(time);
+                            low[neighbor] = time;
+                            time += 1;
+                            stack.push((neighbor, Some(vertex), 0));
+                        }
+                    }
+                } else {
+                    stack.pop();
+                    if let Some(parent) = parent {
+                        low[parent] = low[parent].min(low[vertex]);
+                        if Some(low[vertex]) > discovered[parent] {
+                            bridges.push((Handle(parent.min(vertex)), Handle(parent.max(vertex))));
+                        }
+                    }
+                }
+            }
+        }
+
+        bridges.sort_unstable();
+        bridges
+    }
+
+    /// Runs the depth-first search shared by [Graph::articulation_points] and
+    /// [Graph::biconnected_components], returning which vertices are articulation points
+    /// and the edges of every biconnected component
+    fn biconnected_search(&self) -> (Vec<bool>, Vec<Vec<(Handle, Handle)>>) {
+        let neighbors = self.simple_undirected_neighbors();
+        let mut discovered = vec![None; self.size()];
+        let mut low = vec![0; self.size()];
+        let mut time = 0;
+        let mut is_articulation = vec![false; self.size()];
+        let mut components = vec![];
+        let mut edges = vec![];
+
+        for root in 0..self.size() {
+            if discovered[root].is_some() {
+                continue;
+            }
+            discovered[root] = Some(time);
+            low[root] = time;
+            time += 1;
+            let mut root_children = 0;
+
+            // (vertex, parent, index of the next neighbor to visit)
+            let mut stack = vec![(root, None, 0)];
+            while let Some((vertex, parent, next)) = stack.last_mut() {
+                let (vertex, parent) = (*vertex, *parent);
+                if let Some(&neighbor) = neighbors[vertex].get(*next) {
+                    *next += 1;
+                    if Some(neighbor) == parent {
+                        continue;
+                    }
+                    match discovered[neighbor] {
+                        // the reverse direction of a back edge that is already on the stack
+                        Some(found) if Some(found) > discovered[vertex] => {}
+                        Some(found) => {
+                            low[vertex] = low[vertex].min(found);
+                            edges.push((vertex, neighbor));
+                        }
+                        None => {
+                            discovered[neighbor] = Some(time);
+                            low[neighbor] = time;
+                            time += 1;
+                            edges.push((vertex, neighbor));
+                            stack.push((neighbor, Some(vertex), 0));
+                        }
+                    }
+                } else {
+                    stack.pop();
+                    let Some(parent) = parent else {
+                        continue;
+                    };
+                    low[parent] = low[parent].min(low[vertex]);
+                    if Some(low[vertex]) < discovered[parent] {
+                        continue;
+                    }
+
+                    if parent == root {
+                        root_children += 1;
+                    } else {
+                        is_articulation[parent] = true;
+                    }
+
+                    let mut component = vec![];
+                    while let Some((u, v)) = edges.pop() {
+                        component.push((Handle(u.min(v)), Handle(u.max(v))));
+                        if (u, v) == (parent, vertex) {
+                            break;
+                        }
+                    }
+                    component.sort_unstable();
+                    components.push(component);
+                }
+            }
+
+            if root_children > 1 {
+                is_articulation[root] = true;
+            }
+        }
+
+        components.sort_unstable();
+        (is_articulation, components)
+    }
+
+    /// Returns the articulation points of the graph, i.e. the vertices whose removal increases
+    /// the number of connected components, sorted by handle.
+    ///
+    /// Edges are treated as undirected.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n + m log m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn articulation_points(&self) -> Vec<Handle> {
+        let (is_articulation, _) = self.biconnected_search();
+        (0..self.size())
+            .filter(|&vertex| is_articulation[vertex])
+            .map(Handle)
+            .collect()
+    }
+
+    /// Returns the biconnected components of the graph, i.e. the maximal subgraphs without an [articulation point](Graph::articulation_points).
+    ///
+    /// Every component is returned as its set of edges, where each edge `(u, v)` has `u < v`.
+    /// Edges are treated as undirected and multiple edges between the same two vertices count as a single edge,
+    /// so isolated vertices and self loops do not belong to any component.
+    /// The edges of every component are sorted and the components are ordered by their smallest edge.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n + m log m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn biconnected_components(&self) -> Vec<Vec<(Handle, Handle)>> {
+        let (_, components) = self.biconnected_search();
+        components
+    }
+
+    /// Returns the 2-edge-connected components of the graph, i.e. the maximal sets of vertices
+    /// that stay connected after removing any single edge.
+    ///
+    /// These are the connected components that remain after deleting all [Graph::bridges].
+    /// Edges are treated as undirected. Every component is sorted by handle and the components
+    /// are ordered by their smallest handle.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n + m log m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn two_edge_connected_components(&self) -> Vec<Vec<Handle>> {
+        let bridges: HashSet<_> = self
+            .bridges()
+            .into_iter()
+            .map(|(Handle(u), Handle(v))| (u, v))
+            .collect();
+        let neighbors = self.simple_undirected_neighbors();
+
+        let mut seen = vec![false; self.size()];
+        let mut components = vec![];
+        for root in 0..self.size() {
+            if seen[root] {
+                continue;
+            }
+
+            seen[root] = true;
+            let mut component = vec![];
+            let mut stack = vec![root];
+            while let Some(top) = stack.pop() {
+                component.push(Handle(top));
+                for &neighbor in &neighbors[top] {
+                    if !seen[neighbor] && !bridges.contains(&(top.min(neighbor), top.max(neighbor)))
+                    {
+                        seen[neighbor] = true;
+                        stack.push(neighbor);
+                    }
+                }
+            }
+
+            component.sort_unstable();
+            components.push(component);
+        }
+
+        components
+    }
+
+    /// Finds a directed walk that uses every edge exactly once using Hierholzer's algorithm,
+    /// or returns `None` if no such walk exists.
+    ///
+    /// If every vertex has as many incoming as outgoing edges, the walk is a circuit starting and ending at the
+    /// first vertex that has edges. Otherwise the walk has to start at the unique vertex with one more outgoing
+    /// than incoming edge and ends at the unique vertex with one more incoming than outgoing edge.
+    /// Returns an empty walk if the graph has no edges.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n + m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn eulerian_path(&self) -> Option<Vec<Handle>> {
+        let neighbors: Vec<Vec<usize>> = (0..self.size())
+            .map(|vertex| {
+                self.connected_neighbors(Handle(vertex))
+                    .map(|Handle(neighbor)| neighbor)
+                    .collect()
+            })
+            .collect();
+        let in_degrees = self.in_degrees();
+        let edges: usize = neighbors.iter().map(Vec::len).sum();
+        if edges == 0 {
+            return Some(vec![]);
+        }
+
+        let mut start = None;
+        let mut has_end = false;
+        for (vertex, neighbors) in neighbors.iter().enumerate() {
+            let (out_degree, in_degree) = (neighbors.len(), in_degrees[vertex]);
+            if out_degree == in_degree + 1 && start.is_none() {
+                start = Some(vertex);
+            } else if in_degree == out_degree + 1 && !has_end {
+                has_end = true;
+            } else if in_degree != out_degree {
+                return None;
+            }
+        }
+        // either both the start and the end are unbalanced or the walk is a circuit
+        let start = match start {
+            Some(start) => start,
+            None if !has_end => neighbors
+                .iter()
+                .position(|neighbors| !neighbors.is_empty())?,
+            None => return None,
+        };
+
+        let mut next = vec![0; self.size()];
+        let mut walk = Vec::with_capacity(edges + 1);
+        let mut stack = vec![start];
+        while let Some(&vertex) = stack.last() {
+            match neighbors[vertex].get(next[vertex]) {
+                Some(&neighbor) => {
+                    next[vertex] += 1;
+                    stack.push(neighbor);
+                }
+                None => {
+                    stack.pop();
+                    walk.push(Handle(vertex));
+                }
+            }
+        }
+        walk.reverse();
+
+        // edges that are not reachable from the start are not part of the walk
+        (walk.len() == edges + 1).then_some(walk)
+    }
+
+    /// Groups the vertices of a directed acyclic graph into layers, where the layer of a vertex
+    /// is the length of the longest path from any source (a vertex without incoming edges) to it.
+    ///
+    /// Every edge points from a lower to a higher layer. Vertices within a layer are sorted by handle.
+    /// Returns [CycleError] if the graph contains a cycle.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n + m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn topological_layers(&self) -> Result<Vec<Vec<Handle>>, CycleError> {
+        let order = self.topological_sort()?;
+
+        let mut layer = vec![0; self.size()];
+        for &Handle(vertex) in &order {
+            for Handle(neighbor) in self.connected_neighbors(Handle(vertex)) {
+                layer[neighbor] = layer[neighbor].max(layer[vertex] + 1);
+            }
+        }
+
+        let mut layers = vec![vec![]; layer.iter().max().map_or(0, |&max| max + 1)];
+        for (vertex, &layer) in layer.iter().enumerate() {
+            layers[layer].push(Handle(vertex));
+        }
+        Ok(layers)
+    }
+
+    /// Returns the id of the connected component of every vertex, treating edges as undirected.
+    ///
+    /// Components are numbered from `0` in order of their smallest handle.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n + m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn connected_components(&self) -> Vec<usize> {
+        let neighbors = self.undirected_neighbors();
+        let mut component = vec![None; self.size()];
+        let mut count = 0;
+
+        for root in 0..self.size() {
+            if component[root].is_some() {
+                continue;
+            }
+
+            component[root] = Some(count);
+            let mut stack = vec![root];
+            while let Some(top) = stack.pop() {
+                for &neighbor in &neighbors[top] {
+                    if component[neighbor].is_none() {
+                        component[neighbor] = Some(count);
+                        stack.push(neighbor);
+                    }
+                }
+            }
+            count += 1;
+        }
+
+        component
+            .into_iter()
+            .map(|component| component.expect("every vertex is visited"))
+            .collect()
+    }
+
+    /// Returns the same component ids as [Graph::connected_components], but processes every edge
+    /// once using a union-find structure instead of searching the graph.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O((n + m) α(n))` where `n` is the number of vertices,
+    /// `m` is the number of edges and `α` is the inverse Ackermann function
+    pub fn connected_components_union_find(&self) -> Vec<usize> {
+        let mut sets = DisjointSet::new(self.size());
+        for from in 0..self.size() {
+            for Handle(to) in self.connected_neighbors(Handle(from)) {
+                sets.union(from, to);
+            }
+        }
+
+        let mut ids = vec![None; self.size()];
+        let mut count = 0;
+        (0..self.size())
+            .map(|vertex| {
+                let root = sets.find(vertex);
+                *ids[root].get_or_insert_with(|| {
+                    count += 1;
+                    count - 1
+                })
+            })
+            .collect()
+    }
+
+    /// Finds a shortest path between `start` and `end` that does not pass through any of the `forbidden` vertices,
+    /// or returns `None` if every path between them does.
+    ///
+    /// `start` and `end` themselves may be forbidden, in which case they are still allowed as endpoints.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n + m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn shortest_path_unweighted_avoiding(
+        &self,
+        start: Handle,
+        end: Handle,
+        forbidden: &[Handle],
+    ) -> Option<Vec<Handle>> {
+        let mut blocked = vec![false; self.size()];
+        for &Handle(vertex) in forbidden {
+            blocked[vertex] = true;
+        }
+        blocked[start.0] = false;
+        blocked[end.0] = false;
+
+        let mut queue = VecDeque::new();
+        queue.push_back(start.0);
+        let mut seen = vec![None; self.size()];
+
+        while let Some(front) = queue.pop_front() {
+            if front == end.0 {
+                break;
+            }
+
+            for Handle(neighbor) in self.connected_neighbors(Handle(front)) {
+                if !blocked[neighbor] && seen[neighbor].is_none() {
+                    seen[neighbor] = Some(front);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        let mut path = vec![end];
+        let mut cur = end.0;
+        while cur != start.0 {
+            cur = seen[cur]?;
+            path.push(Handle(cur));
+        }
+
+        path.reverse();
+        Some(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        graph::{Graph, Handle},
+        unweighted::Unweighted,
+        weighted::Weighted,
+        CycleError, DfsOrder, HasEdge,
+    };
+
+    #[test]
+    fn is_connected() {
+        let mut graph: Weighted<_, f32> = ('a'..='f').collect();
+        graph.construct_edges_from(|&from, &to| match (from, to) {
+            ('a', 'b') => Some(9.0),
+            ('a', 'd') => Some(8.0),
+            ('b', 'c') => Some(1.0),
+            ('b', 'e') => Some(3.0),
+            ('c', 'e') => Some(1.0),
+            ('c', 'd') => Some(5.0),
+            ('d', 'f') => Some(8.0),
+            ('e', 'f') => Some(6.0),
+            _ => None,
+        });
+
+        assert!(graph.is_connected());
+
+        graph.remove_edge(
+            graph.get_vertex('a').unwrap(),
+            graph.get_vertex('b').unwrap(),
+        );
+
+        graph.remove_edge(
+            graph.get_vertex('a').unwrap(),
+            graph.get_vertex('d').unwrap(),
+        );
+
+        assert!(!graph.is_connected());
+    }
+
+    #[test]
+    fn connected_long_path() {
+        const N: usize = 100_000;
+
+        // every edge points back towards the first vertex, so every vertex starts its own component
+        let mut path: Unweighted<_> = (0..N).collect();
+        for vertex in 1..N {
+            path.add_edge(Handle(vertex), Handle(vertex - 1));
+        }
+        assert!(path.is_connected());
+
+        path.remove_edge(Handle(N / 2), Handle(N / 2 - 1));
+        assert!(!path.is_connected());
+
+        let mut forward: Unweighted<_> = (0..N).collect();
+        for vertex in 1..N {
+            forward.add_edge(Handle(vertex - 1), Handle(vertex));
+        }
+        assert!(forward.is_connected());
+    }
+
+    #[test]
+    fn connected1() {
+        let mut graph: Unweighted<_> = ('a'..='c').collect();
+        graph.construct_edges_from(|&from, &to| matches!((from, to), ('b', 'a') | ('c', 'a')));
+        assert!(graph.is_connected());
+    }
+
+    fn assert_one_of_paths<V, E>(
+        graph: &Graph<V, E>,
+        actual: Option<Vec<Handle>>,
+        expected: &[&[V]],
+    ) where
+        V: Eq + Clone,
+    {
+        if expected.is_empty() {
+            return;
+        }
+
+        let actual = actual.unwrap();
+
+        for &expected in expected {
+            let expected = expected
+                .iter()
+                .map(|i| graph.get_vertex(i.clone()).unwrap())
+                .collect::<Vec<_>>();
+            if actual == expected {
+                return;
+            }
+        }
+
+        panic!("no path matched with {actual:?}");
+    }
+
+    fn make_graph() -> Unweighted<char> {
+        let mut graph: Unweighted<_> = ('a'..='h').collect();
+        graph.construct_edges_from(|&u, &v| {
+            matches!(
+                (u, v),
+                ('f', 'd')
+                    | ('d', 'h')
+                    | ('c', 'g')
+                    | ('c', 'a')
+                    | ('b', 'f')
+                    | ('b', 'e')
+                    | ('a', 'b')
+                    | ('e', 'h')
+                    | ('d', 'g')
+                    | ('d', 'e')
+                    | ('e', 'c')
+            )
+        });
+        graph
+    }
+
+    #[test]
+    fn shortest_path() {
+        let graph = make_graph();
+
+        let a = graph.get_vertex('a').unwrap();
+        let g = graph.get_vertex('g').unwrap();
+        let d = graph.get_vertex('d').unwrap();
+
+        let path_ag = graph.shortest_path_unweighted(a, g);
+        assert_one_of_paths(
+            &graph,
+            path_ag,
+            &[&['a', 'b', 'e', 'c', 'g'], &['a', 'b', 'f', 'd', 'g']],
+        );
+
+        assert_eq!(graph.shortest_distance_unweighted(a, g), Some(4));
+
+        let path_ad = graph.shortest_path_unweighted(a, d);
+        assert_eq!(
+            path_ad,
+            ['a', 'b', 'f', 'd']
+                .iter()
+                .map(|&i| graph.get_vertex(i))
+                .collect(),
+        );
+    }
+
+    #[test]
+    fn dominators() {
+        let mut graph: Unweighted<_> = (0..7).collect();
+        graph.construct_edges_from(|&u, &v| {
+            matches!(
+                (u, v),
+                (0, 1) | (1, 2) | (1, 3) | (2, 4) | (3, 4) | (4, 1) | (4, 5)
+            )
+        });
+        let handles: Vec<_> = (0..7).map(|i| graph.get_vertex(i).unwrap()).collect();
+
+        let idom = graph.dominators(handles[0]);
+        assert_eq!(
+            idom,
+            [
+                None,
+                Some(handles[0]),
+                Some(handles[1]),
+                Some(handles[1]),
+                Some(handles[1]),
+                Some(handles[4]),
+                // not reachable from the entry
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn reachability() {
+        let mut graph: Unweighted<_> = (0..5).collect();
+        graph.construct_edges_from(|&u, &v| v == u + 1);
+        let handles: Vec<_> = (0..5).map(|i| graph.get_vertex(i).unwrap()).collect();
+
+        assert_eq!(
+            graph.reachable_from(handles[2]),
+            [false, false, true, true, true]
+        );
+        assert!(graph.can_reach(handles[0], handles[4]));
+        assert!(graph.can_reach(handles[3], handles[3]));
+        assert!(!graph.can_reach(handles[4], handles[0]));
+        assert!(!graph.can_reach(handles[2], handles[1]));
+    }
+
+    #[test]
+    fn dfs_order() {
+        //      0
+        //     / \
+        //    1   4
+        //   / \
+        //  2   3
+        let mut tree: Unweighted<_> = (0..5).collect();
+        tree.construct_edges_from(|&u, &v| matches!((u, v), (0, 1) | (0, 4) | (1, 2) | (1, 3)));
+        let root = Handle(0);
+
+        assert_eq!(
+            tree.dfs_order(root, DfsOrder::PreOrder),
+            [Handle(0), Handle(1), Handle(2), Handle(3), Handle(4)]
+        );
+        assert_eq!(
+            tree.dfs_order(root, DfsOrder::PostOrder),
+            [Handle(2), Handle(3), Handle(1), Handle(4), Handle(0)]
+        );
+        assert_eq!(tree.dfs_order(Handle(1), DfsOrder::PreOrder).len(), 3);
+    }
+
+    #[test]
+    fn is_dag() {
+        let mut graph: Unweighted<_> = (1..=12).collect();
+        graph.construct_edges_from(|&u, &v| u != v && v % u == 0);
+        assert!(graph.is_dag());
+        assert!(graph.topological_sort().is_ok());
+
+        // back edge from 12 to 2
+        graph.add_edge(Handle(11), Handle(1));
+        assert!(!graph.is_dag());
+        assert_eq!(graph.topological_sort(), Err(CycleError));
+
+        let mut self_loop: Unweighted<_> = (0..1).collect();
+        self_loop.add_edge(Handle(0), Handle(0));
+        assert!(!self_loop.is_dag());
+        assert!(Unweighted::<()>::new().is_dag());
+    }
+
+    #[test]
+    fn bfs_distances() {
+        let mut path: Unweighted<_> = (0..5).collect();
+        path.construct_edges_from(|&u, &v| v == u + 1);
+
+        assert_eq!(
+            path.bfs_distances(Handle(0)),
+            [Some(0), Some(1), Some(2), Some(3), Some(4)]
+        );
+        assert_eq!(
+            path.bfs_distances(Handle(2)),
+            [None, None, Some(0), Some(1), Some(2)]
+        );
+
+        let graph = make_graph();
+        let distances = graph.bfs_distances(Handle(0));
+        for end in (0..graph.size()).map(Handle) {
+            assert_eq!(
+                distances[end.0],
+                graph.shortest_distance_unweighted(Handle(0), end)
+            );
+        }
+    }
+
+    #[test]
+    fn eulerian_path() {
+        fn assert_uses_every_edge(graph: &Unweighted<i32>, walk: &[Handle]) {
+            let mut walked: Vec<_> = walk.windows(2).map(|pair| (pair[0], pair[1])).collect();
+            let mut edges: Vec<_> = (0..graph.size())
+                .flat_map(|from| {
+                    graph
+                        .neighbors(Handle(from))
+                        .iter()
+                        .map(move |&to| (Handle(from), to))
+                })
+                .collect();
+            walked.sort_unstable();
+            edges.sort_unstable();
+            assert_eq!(walked, edges);
+        }
+
+        // 3 -> 0 -> 1 -> 2 -> 0, so the walk has to start at 3 and end at 0,
+        // while starting at 0 would run around the cycle and miss the edge from 3
+        let mut graph: Unweighted<_> = (0..4).collect();
+        graph.construct_edges_from(|&u, &v| matches!((u, v), (0, 1) | (1, 2) | (2, 0) | (3, 0)));
+        let walk = graph.eulerian_path().unwrap();
+        assert_eq!(
+            walk,
+            [Handle(3), Handle(0), Handle(1), Handle(2), Handle(0)]
+        );
+        assert_uses_every_edge(&graph, &walk);
+
+        let mut circuit: Unweighted<_> = (0..4).collect();
+        circuit.construct_edges_from(|&u, &v| v == (u + 1) % 4);
+        let walk = circuit.eulerian_path().unwrap();
+        assert_eq!(walk.first(), walk.last());
+        assert_uses_every_edge(&circuit, &walk);
+
+        let mut two_starts: Unweighted<_> = (0..3).collect();
+        two_starts.construct_edges_from(|&u, &v| v == 2 && u != 2);
+        assert_eq!(two_starts.eulerian_path(), None);
+
+        let mut disconnected: Unweighted<_> = (0..4).collect();
+        disconnected.construct_edges_from(|&u, &v| u / 2 == v / 2 && u != v);
+        assert_eq!(disconnected.eulerian_path(), None);
+    }
+
+    #[test]
+    fn is_valid_path() {
+        let mut graph: Unweighted<_> = (0..4).collect();
+        graph.construct_edges_from(|&u, &v| v == u + 1);
+
+        assert!(graph.is_valid_path(&[Handle(0), Handle(1), Handle(2), Handle(3)]));
+        assert!(!graph.is_valid_path(&[Handle(0), Handle(2), Handle(3)]));
+        assert!(!graph.is_valid_path(&[Handle(1), Handle(0)]));
+        assert!(graph.is_valid_path(&[Handle(2)]));
+        assert!(graph.is_valid_path(&[]));
+    }
+
+    #[test]
+    fn topological_sort_by() {
+        // 0 -> 3, 1 -> 3, 2 and 4 are independent
+        let mut graph: Unweighted<_> = (0..5).collect();
+        graph.construct_edges_from(|&u, &v| v == 3 && u < 2);
+
+        let ascending = graph.topological_sort_by(|a, b| a.cmp(&b)).unwrap();
+        assert_eq!(
+            ascending,
+            [Handle(0), Handle(1), Handle(2), Handle(3), Handle(4)]
+        );
+        let descending = graph.topological_sort_by(|a, b| b.cmp(&a)).unwrap();
+        assert_eq!(
+            descending,
+            [Handle(4), Handle(2), Handle(1), Handle(0), Handle(3)]
+        );
+
+        graph.add_edge(Handle(3), Handle(0));
+        assert_eq!(graph.topological_sort_by(|a, b| a.cmp(&b)), Err(CycleError));
+    }
+
+    #[test]
+    fn multi_source_bfs() {
+        let mut path: Unweighted<_> = (0..7).collect();
+        path.construct_edges_from(|&u: &i32, &v| u.abs_diff(v) == 1);
+        let sources = [Handle(1), Handle(5)];
+
+        let nearest = path.multi_source_bfs(&sources);
+        assert_eq!(
+            nearest,
+            [
+                Some(1),
+                Some(0),
+                Some(1),
+                Some(2),
+                Some(1),
+                Some(0),
+                Some(1)
+            ]
+        );
+        let from_each: Vec<_> = sources
+            .iter()
+            .map(|&source| path.bfs_distances(source))
+            .collect();
+        for vertex in 0..7 {
+            assert_eq!(
+                nearest[vertex],
+                from_each[0][vertex].min(from_each[1][vertex])
+            );
+        }
+        assert_eq!(path.multi_source_bfs(&[]), [None; 7]);
+    }
+
+    #[test]
+    fn can_reach_matches_shortest_path() {
+        let graph = make_graph();
+        for start in (0..graph.size()).map(Handle) {
+            for end in (0..graph.size()).map(Handle) {
+                assert_eq!(
+                    graph.can_reach(start, end),
+                    graph.shortest_path_unweighted(start, end).is_some()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn condensation() {
+        let mut graph: Unweighted<_> = (0..5).collect();
+        graph.construct_edges_from(|&u, &v| {
+            matches!((u, v), (0, 1) | (1, 2) | (2, 0) | (2, 3) | (1, 3) | (3, 4))
+        });
+        let handles: Vec<_> = (0..5).map(|i| graph.get_vertex(i).unwrap()).collect();
+
+        let condensation = graph.condensation();
+        assert_eq!(condensation.size(), 3);
+        assert_eq!(condensation.num_edges(), 2);
+        assert_eq!(
+            *condensation.vertex_value(Handle(0)),
+            [handles[0], handles[1], handles[2]]
+        );
+
+        // the condensation is acyclic
+        assert!(condensation
+            .strongly_connected_components()
+            .iter()
+            .all(|component| component.len() == 1));
+        for vertex in 0..condensation.size() {
+            assert!(!condensation.has_edge(Handle(vertex), Handle(vertex)));
+        }
+    }
+
+    #[test]
+    fn unique_neighbors() {
+        let mut graph: Unweighted<_> = ('a'..='c').collect();
+        let a = graph.get_vertex('a').unwrap();
+        let b = graph.get_vertex('b').unwrap();
+        let c = graph.get_vertex('c').unwrap();
+        graph.add_edge(a, b);
+        graph.add_edge(a, c);
+        graph.add_edge(a, b);
+
+        assert_eq!(graph.neighbors(a).len(), 3);
+        assert_eq!(graph.unique_neighbors(a).collect::<Vec<_>>(), [b, c]);
+    }
+
+    /// two triangles `0, 1, 2` and `3, 4, 5` joined by the edge `(2, 3)`, with edges in both directions
+    fn two_triangles() -> Unweighted<usize> {
+        let mut graph: Unweighted<_> = (0..6).collect();
+        graph.construct_edges_from(|&u, &v| {
+            matches!(
+                (u.min(v), u.max(v)),
+                (0, 1) | (1, 2) | (0, 2) | (2, 3) | (3, 4) | (4, 5) | (3, 5)
+            )
+        });
+        graph
+    }
+
+    #[test]
+    fn bridges() {
+        let graph = two_triangles();
+        assert_eq!(graph.bridges(), [(Handle(2), Handle(3))]);
+        assert_eq!(
+            graph.two_edge_connected_components(),
+            [
+                vec![Handle(0), Handle(1), Handle(2)],
+                vec![Handle(3), Handle(4), Handle(5)]
+            ]
+        );
+
+        let mut path: Unweighted<_> = (0..4).collect();
+        path.construct_edges_from(|&u, &v| v == u + 1);
+        assert_eq!(path.bridges().len(), 3);
+        assert_eq!(path.two_edge_connected_components().len(), 4);
+    }
+
+    #[test]
+    fn biconnected_components() {
+        let graph = two_triangles();
+        assert_eq!(graph.articulation_points(), [Handle(2), Handle(3)]);
+        assert_eq!(
+            graph.biconnected_components(),
+            [
+                vec![
+                    (Handle(0), Handle(1)),
+                    (Handle(0), Handle(2)),
+                    (Handle(1), Handle(2))
+                ],
+                vec![(Handle(2), Handle(3))],
+                vec![
+                    (Handle(3), Handle(4)),
+                    (Handle(3), Handle(5)),
+                    (Handle(4), Handle(5))
+                ],
+            ]
+        );
+
+        let mut star: Unweighted<_> = (0..4).collect();
+        star.construct_edges_from(|&u, &v| u == 0 && v != 0);
+        assert_eq!(star.articulation_points(), [Handle(0)]);
+        assert_eq!(star.biconnected_components().len(), 3);
+    }
+
+    #[test]
+    fn topological_layers() {
+        let mut diamond: Unweighted<_> = ('a'..='e').collect();
+        diamond.construct_edges_from(|&u, &v| {
+            matches!(
+                (u, v),
+                ('a', 'b') | ('a', 'c') | ('b', 'd') | ('c', 'd') | ('a', 'd')
+            )
+        });
+        let handle = |v| diamond.get_vertex(v).unwrap();
+
+        assert_eq!(
+            diamond.topological_layers(),
+            Ok(vec![
+                vec![handle('a'), handle('e')],
+                vec![handle('b'), handle('c')],
+                vec![handle('d')],
+            ])
+        );
+
+        diamond.add_edge(handle('d'), handle('a'));
+        assert_eq!(diamond.topological_layers(), Err(CycleError));
+    }
+
+    #[test]
+    fn is_connected_matches_weak_connectivity() {
+        fn weakly_connected<V>(graph: &Unweighted<V>) -> bool {
+            let neighbors = graph.undirected_neighbors();
+            let mut seen = vec![false; graph.size()];
+            let mut stack = vec![];
+            if !seen.is_empty() {
+                seen[0] = true;
+                stack.push(0);
+            }
+            while let Some(top) = stack.pop() {
+                for &neighbor in &neighbors[top] {
+                    if !seen[neighbor] {
+                        seen[neighbor] = true;
+                        stack.push(neighbor);
+                    }
+                }
+            }
+            seen.iter().all(|&seen| seen)
+        }
+
+        let conditions: [fn(&u32, &u32) -> bool; 7] = [
+            |_, _| false,
+            |&u, &v| v == u + 1,
+            |&u, &v| u == v + 1,
+            |&u, &v| u != v && v != 0 && u % v == 0,
+            |&u, &v| u != v && (u + v) % 10 == 0,
+            |&u, &v| v == 0 && u != 0,
+            |&u, &v| (u < 4) == (v < 4) && u < v,
+        ];
+
+        for condition in conditions {
+            for size in 1..=8 {
+                let mut graph: Unweighted<_> = (0..size).collect();
+                graph.construct_edges_from(condition);
+                assert_eq!(graph.is_connected(), weakly_connected(&graph));
+            }
+        }
+    }
+
+    #[test]
+    fn connected_components() {
+        let graph = two_triangles();
+        assert_eq!(graph.connected_components(), [0; 6]);
+
+        let mut graph: Unweighted<_> = (0..10).collect();
+        graph.construct_edges_from(|&u, &v| u != v && u % 3 == v % 3 && u < 7);
+        assert_eq!(graph.connected_components(), [0, 1, 2, 0, 1, 2, 0, 1, 2, 0]);
+
+        let conditions: [fn(&u32, &u32) -> bool; 5] = [
+            |_, _| false,
+            |&u, &v| u == v + 2,
+            |&u, &v| u != v && v != 0 && u % v == 0,
+            |&u, &v| (u + v) % 7 == 0,
+            |&u, &v| u > 5 && v == u + 1,
+        ];
+        for condition in conditions {
+            let mut graph: Unweighted<_> = (0..12).collect();
+            graph.construct_edges_from(condition);
+            assert_eq!(
+                graph.connected_components(),
+                graph.connected_components_union_find()
+            );
+        }
+    }
+
+    #[test]
+    fn shortest_path_avoiding() {
+        let graph = make_graph();
+        let handle = |v| graph.get_vertex(v).unwrap();
+        let path = |vertices: &[char]| vertices.iter().map(|&v| handle(v)).collect::<Vec<_>>();
+
+        assert_eq!(
+            graph.shortest_path_unweighted_avoiding(handle('a'), handle('d'), &[]),
+            graph.shortest_path_unweighted(handle('a'), handle('d'))
+        );
+        // the only shortest path a, b, e, h is blocked
+        assert_eq!(
+            graph.shortest_distance_unweighted(handle('a'), handle('h')),
+            Some(3)
+        );
+        assert_eq!(
+            graph.shortest_path_unweighted_avoiding(handle('a'), handle('h'), &[handle('e')]),
+            Some(path(&['a', 'b', 'f', 'd', 'h']))
+        );
+        assert_eq!(
+            graph.shortest_path_unweighted_avoiding(handle('a'), handle('g'), &[handle('f')]),
+            Some(path(&['a', 'b', 'e', 'c', 'g']))
+        );
+        assert_eq!(
+            graph.shortest_path_unweighted_avoiding(handle('a'), handle('d'), &[handle('f')]),
+            None
+        );
+        assert_eq!(
+            graph.shortest_path_unweighted_avoiding(handle('a'), handle('a'), &[handle('a')]),
+            Some(path(&['a']))
         );
     }
 }
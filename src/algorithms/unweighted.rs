@@ -0,0 +1,695 @@
+//! algorithms specific to unweighted graphs
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    graph::Handle,
+    unweighted::Unweighted,
+    util::{DisjointSet, Traversal},
+};
+
+impl<V> Unweighted<V> {
+    /// Detects communities using label propagation, returning a community id for every vertex.
+    ///
+    /// Every vertex starts out in its own community and then repeatedly adopts the label that
+    /// is most frequent among its neighbors, with ties broken randomly. This stops once no
+    /// label changes or after `max_iters` rounds. Edges are treated as undirected.
+    ///
+    /// Community ids are numbered from `0` in order of their first vertex.
+    /// Since vertices are visited in a random order and ties are broken randomly,
+    /// the result is nondeterministic unless `rng` is seeded with a fixed value.
+    ///
+    /// ## Running Time
+    /// Every round has a running time of `O(n + m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn label_propagation(&self, max_iters: usize, rng: &mut impl Rng) -> Vec<usize> {
+        let neighbors = self.undirected_neighbors();
+        let mut labels: Vec<usize> = (0..self.size()).collect();
+        let mut order: Vec<usize> = (0..self.size()).collect();
+
+        let mut counts = HashMap::new();
+        let mut best = vec![];
+        for _ in 0..max_iters {
+            order.shuffle(rng);
+            let mut changed = false;
+
+            for &vertex in &order {
+                if neighbors[vertex].is_empty() {
+                    continue;
+                }
+
+                counts.clear();
+                for &neighbor in &neighbors[vertex] {
+                    *counts.entry(labels[neighbor]).or_insert(0usize) += 1;
+                }
+
+                let max = counts.values().copied().max().unwrap_or(0);
+                best.clear();
+                best.extend(
+                    counts
+                        .iter()
+                        .filter(|(_, &count)| count == max)
+                        .map(|(&label, _)| label),
+                );
+                // sorted so that the choice only depends on the rng
+                best.sort_unstable();
+
+                if best.contains(&labels[vertex]) {
+                    continue;
+                }
+                labels[vertex] = best[rng.gen_range(0..best.len())];
+                changed = true;
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        let mut ids = HashMap::new();
+        labels
+            .iter()
+            .map(|label| {
+                let next = ids.len();
+                *ids.entry(label).or_insert(next)
+            })
+            .collect()
+    }
+
+    /// Samples a spanning tree uniformly at random from all spanning trees of the graph using Wilson's algorithm,
+    /// returning its edges.
+    ///
+    /// Edges are treated as undirected and every tree edge `(u, v)` is returned with `v` being the parent of `u`
+    /// when the tree is rooted at the smallest handle. If the graph is not connected, this returns a uniformly
+    /// random spanning tree of every connected component, so the result has `n - c` edges where `c` is the number of components.
+    ///
+    /// ## Running Time
+    /// This algorithm has an expected running time of `O(n + m + τ)` where `n` is the number of vertices, `m` is the number of edges
+    /// and `τ` is the mean hitting time of a random walk on the graph
+    pub fn random_spanning_tree(&self, rng: &mut impl Rng) -> Vec<(Handle, Handle)> {
+        let neighbors = self.simple_undirected_neighbors();
+        let components = self.connected_components();
+
+        let mut in_tree = vec![false; self.size()];
+        let mut has_root = vec![false; self.size()];
+        for (vertex, &component) in components.iter().enumerate() {
+            if !has_root[component] {
+                has_root[component] = true;
+                in_tree[vertex] = true;
+            }
+        }
+
+        let mut next = vec![0; self.size()];
+        let mut tree = Vec::with_capacity(self.size());
+        for start in 0..self.size() {
+            // loop-erased random walk: revisiting a vertex overwrites its successor, which erases the loop
+            let mut vertex = start;
+            while !in_tree[vertex] {
+                next[vertex] = neighbors[vertex][rng.gen_range(0..neighbors[vertex].len())];
+                vertex = next[vertex];
+            }
+
+            let mut vertex = start;
+            while !in_tree[vertex] {
+                in_tree[vertex] = true;
+                tree.push((Handle(vertex), Handle(next[vertex])));
+                vertex = next[vertex];
+            }
+        }
+
+        tree
+    }
+
+    /// Returns the `k`-th power of the graph, which has an edge from `u` to `w` if and only if
+    /// there is a walk of at least one and at most `k` edges from `u` to `w` in this graph.
+    ///
+    /// The vertices (and handles) stay the same. Paths of length zero do not count, so `u` only gets a
+    /// self loop if it lies on a cycle of at most `k` edges, and `power(1)` is this graph without parallel edges.
+    /// The neighbors of every vertex are sorted by handle.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n (n + m))` where `n` is the number of vertices and `m` is the number of edges
+    pub fn power(&self, k: usize) -> Unweighted<V>
+    where
+        V: Clone,
+    {
+        let mut edges = Vec::with_capacity(self.size());
+        let mut distance = vec![None; self.size()];
+        for start in 0..self.size() {
+            distance.fill(None);
+            distance[start] = Some(0);
+            let mut queue = VecDeque::from([start]);
+            let mut reached = vec![];
+            let mut on_cycle = false;
+
+            while let Some(front) = queue.pop_front() {
+                let next = distance[front].map_or(0, |distance| distance + 1);
+                if next > k {
+                    break;
+                }
+                for &Handle(neighbor) in &self.edges[front] {
+                    on_cycle |= neighbor == start;
+                    if distance[neighbor].is_none() {
+                        distance[neighbor] = Some(next);
+                        reached.push(Handle(neighbor));
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+
+            if on_cycle {
+                reached.push(Handle(start));
+            }
+            reached.sort_unstable();
+            edges.push(reached);
+        }
+
+        Unweighted {
+            vertices: self.vertices.clone(),
+            edges,
+        }
+    }
+
+    /// Finds the lowest common ancestor of `a` and `b` in the tree rooted at `root`,
+    /// i.e. the vertex furthest from `root` that lies on the paths from `root` to both `a` and `b`.
+    ///
+    /// Edges are treated as undirected, so the tree may be stored with edges in either or both directions.
+    /// Returns `None` if `a` or `b` is not connected to `root`.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n + m)` where `n` is the number of vertices and `m` is the number of edges.
+    /// For many queries on the same tree, use [Unweighted::lca_batch] instead
+    pub fn lca(&self, root: Handle, a: Handle, b: Handle) -> Option<Handle> {
+        let neighbors = self.undirected_neighbors();
+        let mut parent = vec![None; self.size()];
+        let mut depth = vec![None; self.size()];
+        depth[root.0] = Some(0usize);
+
+        let mut stack = vec![root.0];
+        while let Some(top) = stack.pop() {
+            for &neighbor in &neighbors[top] {
+                if depth[neighbor].is_none() {
+                    depth[neighbor] = depth[top].map(|depth| depth + 1);
+                    parent[neighbor] = Some(top);
+                    stack.push(neighbor);
+                }
+            }
+        }
+
+        let (mut a, mut b) = (a.0, b.0);
+        let (mut depth_a, mut depth_b) = (depth[a]?, depth[b]?);
+        while depth_a > depth_b {
+            a = parent[a]?;
+            depth_a -= 1;
+        }
+        while depth_b > depth_a {
+            b = parent[b]?;
+            depth_b -= 1;
+        }
+        while a != b {
+            a = parent[a]?;
+            b = parent[b]?;
+        }
+
+        Some(Handle(a))
+    }
+
+    /// Answers many lowest common ancestor queries (see [Unweighted::lca]) on the tree rooted at `root`
+    /// at once, using Tarjan's offline algorithm.
+    ///
+    /// The answers are returned in the order of `queries`, with `None` for queries where a vertex
+    /// is not connected to `root`.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O((n + m + q) α(n))` where `n` is the number of vertices,
+    /// `m` is the number of edges, `q` is the number of queries and `α` is the inverse Ackermann function
+    pub fn lca_batch(&self, root: Handle, queries: &[(Handle, Handle)]) -> Vec<Option<Handle>> {
+        let neighbors = self.undirected_neighbors();
+
+        let mut queries_of = vec![vec![]; self.size()];
+        for (i, &(a, b)) in queries.iter().enumerate() {
+            queries_of[a.0].push((b.0, i));
+            queries_of[b.0].push((a.0, i));
+        }
+
+        let mut answers = vec![None; queries.len()];
+        let mut sets = DisjointSet::new(self.size());
+        let mut ancestor: Vec<usize> = (0..self.size()).collect();
+        let mut seen = vec![false; self.size()];
+        let mut finished = vec![false; self.size()];
+
+        seen[root.0] = true;
+        // (vertex, index of the next neighbor to visit)
+        let mut stack = vec![(root.0, 0)];
+        while let Some((vertex, next)) = stack.last_mut() {
+            let vertex = *vertex;
+            if let Some(&neighbor) = neighbors[vertex].get(*next) {
+                *next += 1;
+                if !seen[neighbor] {
+                    seen[neighbor] = true;
+                    stack.push((neighbor, 0));
+                }
+                continue;
+            }
+
+            finished[vertex] = true;
+            for &(other, i) in &queries_of[vertex] {
+                if finished[other] {
+                    answers[i] = Some(Handle(ancestor[sets.find(other)]));
+                }
+            }
+
+            stack.pop();
+            if let Some(&(parent, _)) = stack.last() {
+                sets.union(parent, vertex);
+                let representative = sets.find(parent);
+                ancestor[representative] = parent;
+            }
+        }
+
+        answers
+    }
+
+    /// Returns the eccentricity of `vertex`, i.e. the largest number of edges on a shortest path
+    /// from `vertex` to any other vertex.
+    ///
+    /// Edges are directed and only vertices reachable from `vertex` are taken into account,
+    /// so in a disconnected graph this is the eccentricity within the set of vertices reachable from `vertex`.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n + m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn eccentricity(&self, vertex: Handle) -> usize {
+        self.bfs_distances(vertex)
+            .into_iter()
+            .flatten()
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns the center of the graph, i.e. the vertices with the smallest [eccentricity](Unweighted::eccentricity),
+    /// sorted by handle.
+    ///
+    /// Since the eccentricity only takes reachable vertices into account, a vertex that can not reach any
+    /// other vertex has an eccentricity of zero and therefore always belongs to the center.
+    /// For disconnected graphs, it is usually more meaningful to compute the center of every component separately.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n (n + m))` where `n` is the number of vertices and `m` is the number of edges
+    pub fn center(&self) -> Vec<Handle> {
+        self.extreme_eccentricity(|eccentricity, best| eccentricity < best)
+    }
+
+    /// Returns the periphery of the graph, i.e. the vertices with the largest [eccentricity](Unweighted::eccentricity),
+    /// sorted by handle.
+    ///
+    /// See [Unweighted::center] for how disconnected graphs are handled.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n (n + m))` where `n` is the number of vertices and `m` is the number of edges
+    pub fn periphery(&self) -> Vec<Handle> {
+        self.extreme_eccentricity(|eccentricity, best| eccentricity > best)
+    }
+
+    /// Returns the values of the vertices reachable from `start` in the order in which a breadth-first search visits them.
+    ///
+    /// ```
+    /// # use comtesse::unweighted::Unweighted;
+    /// let mut graph: Unweighted<_> = ["root", "left", "right", "leaf"].into_iter().collect();
+    /// graph.construct_edges_from(|&u, &v| matches!((u, v), ("root", "left" | "right") | ("left", "leaf")));
+    ///
+    /// let root = graph.get_vertex("root").unwrap();
+    /// for value in graph.bfs_values(root) {
+    ///     println!("{value}");
+    /// }
+    /// let order: Vec<_> = graph.bfs_values(root).copied().collect();
+    /// assert_eq!(order, ["root", "left", "right", "leaf"]);
+    /// ```
+    ///
+    /// ## Running Time
+    /// The search runs when this is called and has a running time of `O(n + m)`
+    /// where `n` is the number of vertices and `m` is the number of edges
+    pub fn bfs_values(&self, start: Handle) -> impl Iterator<Item = &V> {
+        let mut order = vec![];
+        Traversal::new().bfs_into(self, start, &mut order);
+        order.into_iter().map(|vertex| self.vertex_value(vertex))
+    }
+
+    /// Finds a shortest path that starts at the first of the `waypoints`, visits all of them in the given order
+    /// and ends at the last one, or returns `None` if one of the waypoints is not reachable from the previous one.
+    ///
+    /// The path is the concatenation of the shortest paths between consecutive waypoints,
+    /// so it may visit vertices more than once. An empty list of waypoints gives an empty path.
+    ///
+    /// ```
+    /// # use comtesse::unweighted::Unweighted;
+    /// let mut graph: Unweighted<_> = (0..4).collect();
+    /// graph.construct_edges_from(|&u, &v| v == (u + 1) % 4);
+    /// let stops: Vec<_> = [0, 2, 1].iter().map(|&i| graph.get_vertex(i).unwrap()).collect();
+    ///
+    /// let path = graph.shortest_path_through(&stops).unwrap();
+    /// assert_eq!(path.len(), 1 + 2 + 3);
+    /// ```
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(k (n + m))` where `k` is the number of waypoints,
+    /// `n` is the number of vertices and `m` is the number of edges
+    pub fn shortest_path_through(&self, waypoints: &[Handle]) -> Option<Vec<Handle>> {
+        let Some(&first) = waypoints.first() else {
+            return Some(vec![]);
+        };
+        let mut path = vec![first];
+        for segment in waypoints.windows(2) {
+            let part = self.shortest_path_unweighted(segment[0], segment[1])?;
+            path.extend_from_slice(&part[1..]);
+        }
+        Some(path)
+    }
+
+    /// Returns whether `pattern` is a minor of the graph, i.e. whether a graph isomorphic to `pattern`
+    /// can be obtained by deleting vertices and edges and by contracting edges.
+    ///
+    /// Both graphs are treated as simple undirected graphs, ignoring the direction of edges, self loops,
+    /// multiple edges and the values of the vertices. For example, a graph is planar if and only if it
+    /// contains neither the complete graph on five vertices nor the complete bipartite graph on three and three vertices as a minor.
+    ///
+    /// ```
+    /// # use comtesse::unweighted::Unweighted;
+    /// let mut triangle: Unweighted<_> = (0..3).collect();
+    /// triangle.construct_edges_from(|&u, &v| v == (u + 1) % 3);
+    ///
+    /// let mut cycle: Unweighted<_> = (0..6).collect();
+    /// cycle.construct_edges_from(|&u, &v| v == (u + 1) % 6);
+    /// assert!(cycle.contains_minor(&triangle));
+    /// ```
+    ///
+    /// ## Panics
+    ///
+    /// Panics if either graph has more than 64 vertices
+    ///
+    /// ## Running Time
+    /// This branches over all sequences of vertex deletions and edge contractions, so the running time is
+    /// exponential in the number of vertices. It is only practical for graphs with up to about a dozen vertices.
+    pub fn contains_minor<U>(&self, pattern: &Unweighted<U>) -> bool {
+        let pattern = adjacency_masks(pattern);
+        search_minor(&adjacency_masks(self), &pattern, &mut HashSet::new())
+    }
+
+    /// Returns the vertices whose eccentricity is not beaten by any other vertex according to `better`
+    fn extreme_eccentricity(&self, better: impl Fn(usize, usize) -> bool) -> Vec<Handle> {
+        let mut best = None;
+        let mut vertices = vec![];
+        for vertex in (0..self.size()).map(Handle) {
+            let eccentricity = self.eccentricity(vertex);
+            match best {
+                Some(best) if eccentricity == best => vertices.push(vertex),
+                Some(best) if !better(eccentricity, best) => {}
+                _ => {
+                    best = Some(eccentricity);
+                    vertices.clear();
+                    vertices.push(vertex);
+                }
+            }
+        }
+        vertices
+    }
+}
+
+/// Returns the simple undirected graph underlying `graph` as one bitmask of neighbors per vertex
+fn adjacency_masks<V>(graph: &Unweighted<V>) -> Vec<u64> {
+    assert!(graph.size() <= 64, "at most 64 vertices are supported");
+    graph
+        .simple_undirected_neighbors()
+        .iter()
+        .map(|neighbors| {
+            neighbors
+                .iter()
+                .fold(0, |mask, &neighbor| mask | 1 << neighbor)
+        })
+        .collect()
+}
+
+fn count_edges(adjacency: &[u64]) -> u32 {
+    adjacency.iter().map(|mask| mask.count_ones()).sum::<u32>() / 2
+}
+
+/// Removes `vertex`, shifting the vertices after it down by one
+fn without_vertex(adjacency: &[u64], vertex: usize) -> Vec<u64> {
+    let below = (1 << vertex) - 1;
+    adjacency
+        .iter()
+        .enumerate()
+        .filter(|&(other, _)| other != vertex)
+        .map(|(_, &mask)| mask & below | (mask >> 1) & !below)
+        .collect()
+}
+
+/// Contracts the edge between `vertex` and `into`, keeping `into`
+fn contract(adjacency: &[u64], vertex: usize, into: usize) -> Vec<u64> {
+    let mut merged = adjacency.to_vec();
+    merged[into] |= merged[vertex];
+    merged[into] &= !(1 << into);
+    for (other, mask) in merged.iter_mut().enumerate() {
+        if *mask & 1 << vertex != 0 && other != into {
+            *mask |= 1 << into;
+        }
+    }
+    without_vertex(&merged, vertex)
+}
+
+/// Returns whether `pattern` is a minor of `graph`, skipping graphs that were already ruled out
+fn search_minor(graph: &[u64], pattern: &[u64], ruled_out: &mut HashSet<Vec<u64>>) -> bool {
+    if graph.len() < pattern.len() || count_edges(graph) < count_edges(pattern) {
+        return false;
+    }
+    if !ruled_out.insert(graph.to_vec()) {
+        return false;
+    }
+    if graph.len() == pattern.len() {
+        return embed(graph, pattern, &mut vec![], 0);
+    }
+
+    // some vertex is either deleted or merged with a neighbor from the same branch set
+    (0..graph.len()).any(|vertex| {
+        search_minor(&without_vertex(graph, vertex), pattern, ruled_out)
+            || (0..graph.len())
+                .filter(|&neighbor| graph[vertex] & 1 << neighbor != 0)
+                .any(|neighbor| {
+                    search_minor(&contract(graph, vertex, neighbor), pattern, ruled_out)
+                })
+    })
+}
+
+/// Tries to extend `mapped`, which maps the first vertices of `pattern` to distinct vertices of `host`,
+/// such that every edge of `pattern` is mapped to an edge of `host`
+fn embed(host: &[u64], pattern: &[u64], mapped: &mut Vec<usize>, used: u64) -> bool {
+    let next = mapped.len();
+    if next == pattern.len() {
+        return true;
+    }
+    for candidate in 0..host.len() {
+        let edges_kept = mapped.iter().enumerate().all(|(prev, &target)| {
+            pattern[next] & 1 << prev == 0 || host[candidate] & 1 << target != 0
+        });
+        if used & 1 << candidate == 0 && edges_kept {
+            mapped.push(candidate);
+            if embed(host, pattern, mapped, used | 1 << candidate) {
+                return true;
+            }
+            mapped.pop();
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{graph::Handle, unweighted::Unweighted, util::DisjointSet, HasEdge};
+
+    #[test]
+    fn label_propagation_two_clusters() {
+        let mut graph: Unweighted<_> = (0..10).collect();
+        graph.construct_edges_from(|&u, &v| u != v && (u < 5) == (v < 5));
+        graph.add_edge(graph.get_vertex(4).unwrap(), graph.get_vertex(5).unwrap());
+
+        // the planted clusters may get any labels, but vertices in the same cluster must share theirs
+        let mut rng = StdRng::seed_from_u64(0xc0417e55e);
+        let communities = graph.label_propagation(100, &mut rng);
+        let (left, right) = communities.split_at(5);
+        assert!(left.iter().all(|&label| label == left[0]));
+        assert!(right.iter().all(|&label| label == right[0]));
+        assert_ne!(left[0], right[0]);
+    }
+
+    #[test]
+    fn lca() {
+        //         0
+        //       /   \
+        //      1     2
+        //     / \     \
+        //    3   4     5
+        //       / \
+        //      6   7
+        let mut tree: Unweighted<_> = (0..9).collect();
+        tree.construct_edges_from(|&parent, &child| {
+            matches!(
+                (parent, child),
+                (0, 1) | (0, 2) | (1, 3) | (1, 4) | (2, 5) | (4, 6) | (4, 7)
+            )
+        });
+        let root = Handle(0);
+
+        assert_eq!(tree.lca(root, Handle(6), Handle(3)), Some(Handle(1)));
+        assert_eq!(tree.lca(root, Handle(7), Handle(5)), Some(Handle(0)));
+        assert_eq!(tree.lca(root, Handle(4), Handle(7)), Some(Handle(4)));
+        assert_eq!(tree.lca(root, Handle(8), Handle(7)), None);
+
+        let queries: Vec<_> = (0..9)
+            .flat_map(|a| (0..9).map(move |b| (Handle(a), Handle(b))))
+            .collect();
+        let expected: Vec<_> = queries.iter().map(|&(a, b)| tree.lca(root, a, b)).collect();
+        assert_eq!(tree.lca_batch(root, &queries), expected);
+        assert_eq!(tree.lca_batch(Handle(4), &queries).len(), queries.len());
+    }
+
+    #[test]
+    fn center_and_periphery() {
+        let mut path: Unweighted<_> = (0..5).collect();
+        path.construct_edges_from(|&u: &i32, &v| u.abs_diff(v) == 1);
+
+        let eccentricities: Vec<_> = (0..5).map(|v| path.eccentricity(Handle(v))).collect();
+        assert_eq!(eccentricities, [4, 3, 2, 3, 4]);
+        assert_eq!(path.center(), [Handle(2)]);
+        assert_eq!(path.periphery(), [Handle(0), Handle(4)]);
+
+        let mut cycle: Unweighted<_> = (0..4).collect();
+        cycle.construct_edges_from(|&u, &v| v == (u + 1) % 4);
+        assert_eq!(cycle.center().len(), 4);
+        assert!(Unweighted::<()>::new().center().is_empty());
+    }
+
+    #[test]
+    fn random_spanning_tree() {
+        let mut rng = StdRng::seed_from_u64(0x5e1f);
+
+        let mut grid: Unweighted<_> = (0..16).collect();
+        grid.construct_edges_from(|&u: &i32, &v| {
+            (u / 4 == v / 4 && u.abs_diff(v) == 1) || u.abs_diff(v) == 4
+        });
+        for _ in 0..20 {
+            let tree = grid.random_spanning_tree(&mut rng);
+            assert_eq!(tree.len(), 15);
+
+            let mut sets = DisjointSet::new(16);
+            for &(u, v) in &tree {
+                assert!(grid.has_edge(u, v));
+                assert!(sets.union(u.0, v.0));
+            }
+            assert!((1..16).all(|v| sets.same_set(0, v)));
+        }
+
+        let mut two_paths: Unweighted<_> = (0..6).collect();
+        two_paths.construct_edges_from(|&u, &v| v == u + 1 && v != 3);
+        assert_eq!(two_paths.random_spanning_tree(&mut rng).len(), 4);
+
+        // a triangle with the edges between 0 and 1 stored in both directions and the other two only once,
+        // each of its three spanning trees leaves out a different edge
+        let mut triangle: Unweighted<_> = (0..3).collect();
+        triangle.construct_edges_from(|&u, &v| u < v || (u, v) == (1, 0));
+        let mut left_out = [0; 3];
+        for _ in 0..3000 {
+            let tree = triangle.random_spanning_tree(&mut rng);
+            let covers = |a: usize, b: usize| {
+                tree.iter()
+                    .any(|&(u, v)| (u.0, v.0) == (a, b) || (u.0, v.0) == (b, a))
+            };
+            let missing = [(0, 1), (0, 2), (1, 2)].map(|(a, b)| !covers(a, b));
+            assert_eq!(missing.iter().filter(|&&missing| missing).count(), 1);
+            left_out[missing.iter().position(|&missing| missing).unwrap()] += 1;
+        }
+        assert!(
+            left_out.iter().all(|&count| (850..=1150).contains(&count)),
+            "{left_out:?}"
+        );
+    }
+
+    #[test]
+    fn power() {
+        let mut path: Unweighted<_> = (0..5).collect();
+        path.construct_edges_from(|&u, &v| v == u + 1);
+
+        let square = path.power(2);
+        assert_eq!(square.neighbors(Handle(0)), [Handle(1), Handle(2)]);
+        assert_eq!(square.neighbors(Handle(2)), [Handle(3), Handle(4)]);
+        assert_eq!(square.neighbors(Handle(3)), [Handle(4)]);
+        assert_eq!(square.num_edges(), 7);
+        assert!(path.power(1).edge_difference(&path).is_empty());
+        assert_eq!(path.power(10).num_edges(), 10);
+
+        let mut triangle: Unweighted<_> = (0..3).collect();
+        triangle.construct_edges_from(|&u, &v| v == (u + 1) % 3);
+        assert_eq!(triangle.power(2).num_edges(), 6);
+        assert_eq!(
+            triangle.power(3).neighbors(Handle(1)),
+            [Handle(0), Handle(1), Handle(2)]
+        );
+    }
+
+    #[test]
+    fn shortest_path_through() {
+        // a cycle 0 -> 1 -> 2 -> 3 -> 0 with a dead end 1 -> 4
+        let mut graph: Unweighted<_> = (0..5).collect();
+        graph.construct_edges_from(|&u, &v| (v == (u + 1) % 4 && u < 4) || (u, v) == (1, 4));
+        let [a, b, c, d, e] = [0, 1, 2, 3, 4].map(Handle);
+
+        let waypoints = [c, a, d];
+        let path = graph.shortest_path_through(&waypoints).unwrap();
+        assert_eq!(path, [c, d, a, b, c, d]);
+        assert!(path.windows(2).all(|pair| graph.has_edge(pair[0], pair[1])));
+        let mut remaining = path.iter();
+        assert!(waypoints
+            .iter()
+            .all(|waypoint| remaining.any(|vertex| vertex == waypoint)));
+
+        assert_eq!(graph.shortest_path_through(&[b]), Some(vec![b]));
+        assert_eq!(graph.shortest_path_through(&[]), Some(vec![]));
+        assert_eq!(graph.shortest_path_through(&[a, e, a]), None);
+    }
+
+    #[test]
+    fn contains_minor() {
+        let complete = |n| {
+            let mut graph: Unweighted<_> = (0..n).collect();
+            graph.construct_edges_from(|&u, &v| u < v);
+            graph
+        };
+        let k5 = complete(5);
+        assert!(complete(6).contains_minor(&k5));
+        assert!(k5.contains_minor(&k5));
+        assert!(!complete(4).contains_minor(&k5));
+
+        let mut cycle: Unweighted<_> = (0..8).collect();
+        cycle.construct_edges_from(|&u, &v| v == (u + 1) % 8);
+        assert!(!cycle.contains_minor(&k5));
+        assert!(cycle.contains_minor(&complete(3)));
+        assert!(!cycle.contains_minor(&complete(4)));
+
+        // the outer cycle, the spokes and the inner pentagram of the Petersen graph
+        let mut petersen: Unweighted<_> = (0..10).collect();
+        petersen.construct_edges_from(|&u, &v| match (u < 5, v < 5) {
+            (true, true) => v == (u + 1) % 5,
+            (true, false) => v == u + 5,
+            (false, false) => v == (u - 5 + 2) % 5 + 5,
+            (false, true) => false,
+        });
+        assert_eq!(petersen.num_edges(), 15);
+        assert!(petersen.contains_minor(&k5));
+
+        let mut grid: Unweighted<_> = (0..9).collect();
+        grid.construct_edges_from(|&u, &v| (v == u + 1 && v % 3 != 0) || v == u + 3);
+        assert!(!grid.contains_minor(&k5));
+        assert!(grid.contains_minor(&complete(4)));
+    }
+}
@@ -0,0 +1,1920 @@
+//! algorithms specific to weighted graphs
+
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, VecDeque},
+};
+
+use crate::{
+    graph::Handle,
+    util::DisjointSet,
+    weighted::{compare_weights, Weighted},
+    CycleError, NegativeCycle,
+};
+
+/// Entry of a priority queue ordered so that the [BinaryHeap] pops the smallest weight first
+struct MinScored<W>(W, usize);
+
+impl<W: PartialOrd> PartialEq for MinScored<W> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<W: PartialOrd> Eq for MinScored<W> {}
+
+impl<W: PartialOrd> PartialOrd for MinScored<W> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<W: PartialOrd> Ord for MinScored<W> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_weights(&other.0, &self.0)
+    }
+}
+
+/// Follows `pred` back from `end` to `start`, returning the path from `start` to `end`
+/// or `None` if `end` was not reached
+fn path_from_predecessors(
+    pred: &[Option<Handle>],
+    start: Handle,
+    end: Handle,
+) -> Option<Vec<Handle>> {
+    let mut path = vec![end];
+    let mut cur = end;
+    while cur != start {
+        cur = pred[cur.0]?;
+        path.push(cur);
+    }
+    path.reverse();
+    Some(path)
+}
+
+/// Up to this many odd vertices, [Weighted::chinese_postman] matches them exactly
+const EXACT_MATCHING_LIMIT: usize = 16;
+
+/// Finds a minimum weight perfect matching of the `k` vertices whose pairwise distances are given by `between`
+/// with a dynamic program over all subsets, returning the matched pairs. Takes `O(2^k k)` time
+fn exact_matching<W>(between: &[Vec<Option<W>>]) -> Option<Vec<(usize, usize)>>
+where
+    W: num_traits::Num + Copy + PartialOrd,
+{
+    // matched[mask] is the cheapest matching of the vertices in `mask`
+    // together with the pair that was matched last
+    let full = (1usize << between.len()) - 1;
+    let mut matched: Vec<Option<(W, usize, usize)>> = vec![None; full + 1];
+    matched[0] = Some((W::zero(), 0, 0));
+    for mask in 0..full {
+        let Some((cost, _, _)) = matched[mask] else {
+            continue;
+        };
+        let first = (!mask).trailing_zeros() as usize;
+        for (second, &distance) in between[first].iter().enumerate().skip(first + 1) {
+            if mask & (1 << second) != 0 {
+                continue;
+            }
+            let Some(distance) = distance else {
+                continue;
+            };
+            let next = mask | (1 << first) | (1 << second);
+            let candidate = cost + distance;
+            if matched[next].is_none_or(|(best, _, _)| candidate < best) {
+                matched[next] = Some((candidate, first, second));
+            }
+        }
+    }
+
+    let mut pairs = vec![];
+    let mut mask = full;
+    while mask != 0 {
+        let (_, first, second) = matched[mask]?;
+        pairs.push((first, second));
+        mask &= !((1 << first) | (1 << second));
+    }
+    Some(pairs)
+}
+
+/// Matches the vertices whose pairwise distances are given by `between` greedily,
+/// always pairing the two closest unmatched vertices. Takes `O(k^2 log k)` time
+fn greedy_matching<W>(between: &[Vec<Option<W>>]) -> Option<Vec<(usize, usize)>>
+where
+    W: num_traits::Num + Copy + PartialOrd,
+{
+    let mut candidates = vec![];
+    for (first, row) in between.iter().enumerate() {
+        for (second, &distance) in row.iter().enumerate().skip(first + 1) {
+            candidates.push((distance?, first, second));
+        }
+    }
+    candidates.sort_by(|a, b| compare_weights(&a.0, &b.0));
+
+    let mut free = vec![true; between.len()];
+    let mut pairs = vec![];
+    for (_, first, second) in candidates {
+        if free[first] && free[second] {
+            free[first] = false;
+            free[second] = false;
+            pairs.push((first, second));
+        }
+    }
+    Some(pairs)
+}
+
+impl<V, W> Weighted<V, W>
+where
+    W: num_traits::Num + Copy + PartialOrd,
+{
+    /// Runs the Bellman-Ford relaxation from `start`.
+    ///
+    /// Returns the distances, the predecessor of every vertex and a vertex that could still
+    /// be relaxed after `n - 1` rounds, which exists if and only if a negative cycle is reachable from `start`
+    fn bellman_ford_core(
+        &self,
+        start: Handle,
+    ) -> (Vec<Option<W>>, Vec<Option<usize>>, Option<usize>) {
+        let mut dist = vec![None; self.size()];
+        let mut pred = vec![None; self.size()];
+        dist[start.0] = Some(W::zero());
+
+        for round in 0..self.size() {
+            let mut relaxed = None;
+            for from in 0..self.size() {
+                let Some(from_dist) = dist[from] else {
+                    continue;
+                };
+                for connection in &self.edges[from] {
+                    let to = connection.pointing_to().0;
+                    let candidate = from_dist + connection.weight();
+                    if dist[to].is_none_or(|to_dist| candidate < to_dist) {
+                        dist[to] = Some(candidate);
+                        pred[to] = Some(from);
+                        relaxed = Some(to);
+                    }
+                }
+            }
+
+            match relaxed {
+                None => return (dist, pred, None),
+                Some(vertex) if round + 1 == self.size() => return (dist, pred, Some(vertex)),
+                Some(_) => {}
+            }
+        }
+
+        (dist, pred, None)
+    }
+
+    /// Finds the shortest distance from `start` to every vertex, allowing negative edge weights.
+    /// Vertices that are not reachable from `start` have distance `None`.
+    ///
+    /// Returns [NegativeCycle] if a negative cycle is reachable from `start`
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n * m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn bellman_ford(&self, start: Handle) -> Result<Vec<Option<W>>, NegativeCycle> {
+        match self.bellman_ford_core(start) {
+            (dist, _, None) => Ok(dist),
+            (_, _, Some(_)) => Err(NegativeCycle),
+        }
+    }
+
+    /// Finds the shortest distance from `start` to every vertex, allowing negative edge weights,
+    /// using the Shortest Path Faster Algorithm. Vertices that are not reachable from `start` have distance `None`.
+    ///
+    /// This gives the same result as [Weighted::bellman_ford], but only relaxes the edges of vertices
+    /// whose distance changed, which is usually much faster on sparse graphs.
+    ///
+    /// Returns [NegativeCycle] if a negative cycle is reachable from `start`
+    ///
+    /// ## Running Time
+    /// This algorithm has a worst case running time of `O(n * m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn shortest_path_spfa(&self, start: Handle) -> Result<Vec<Option<W>>, NegativeCycle> {
+        let mut dist = vec![None; self.size()];
+        // the number of edges on the path to every vertex, which can only reach `n` on a negative cycle
+        let mut edges_on_path = vec![0; self.size()];
+        let mut queued = vec![false; self.size()];
+        dist[start.0] = Some(W::zero());
+
+        let mut queue = VecDeque::from([start.0]);
+        queued[start.0] = true;
+        while let Some(from) = queue.pop_front() {
+            queued[from] = false;
+            let Some(from_dist) = dist[from] else {
+                continue;
+            };
+            for connection in &self.edges[from] {
+                let to = connection.pointing_to().0;
+                let candidate = from_dist + connection.weight();
+                if dist[to].is_none_or(|to_dist| candidate < to_dist) {
+                    dist[to] = Some(candidate);
+                    edges_on_path[to] = edges_on_path[from] + 1;
+                    if edges_on_path[to] >= self.size() {
+                        return Err(NegativeCycle);
+                    }
+                    if !queued[to] {
+                        queued[to] = true;
+                        queue.push_back(to);
+                    }
+                }
+            }
+        }
+
+        Ok(dist)
+    }
+
+    /// Finds a cycle of negative total weight that is reachable from `start`,
+    /// or returns `None` if no such cycle exists.
+    ///
+    /// The cycle is returned as a sequence of vertices where each vertex has an edge to the next one
+    /// and the last vertex has an edge back to the first one.
+    ///
+    /// ```
+    /// # use comtesse::weighted::Weighted;
+    /// let mut graph: Weighted<_, i32> = ('a'..='d').collect();
+    /// graph.construct_edges_from(|&u, &v| match (u, v) {
+    ///     ('a', 'b') => Some(1),
+    ///     ('b', 'c') => Some(2),
+    ///     ('c', 'd') => Some(-4),
+    ///     ('d', 'b') => Some(1),
+    ///     _ => None,
+    /// });
+    ///
+    /// let cycle = graph.find_negative_cycle(graph.get_vertex('a').unwrap()).unwrap();
+    /// assert_eq!(cycle.len(), 3);
+    /// ```
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n * m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn find_negative_cycle(&self, start: Handle) -> Option<Vec<Handle>> {
+        let (_, pred, relaxed) = self.bellman_ford_core(start);
+
+        // after n steps back along the predecessors we are guaranteed to be on the cycle
+        let mut on_cycle = relaxed?;
+        for _ in 0..self.size() {
+            on_cycle = pred[on_cycle].expect("relaxed vertices have a predecessor");
+        }
+
+        let mut cycle = vec![Handle(on_cycle)];
+        let mut cur = pred[on_cycle].expect("vertices on the cycle have a predecessor");
+        while cur != on_cycle {
+            cycle.push(Handle(cur));
+            cur = pred[cur].expect("vertices on the cycle have a predecessor");
+        }
+        cycle.reverse();
+
+        Some(cycle)
+    }
+
+    /// Finds the shortest distance from `start` to every vertex in a directed acyclic graph.
+    /// Vertices that are not reachable from `start` have distance `None`.
+    ///
+    /// Negative edge weights are supported, since an acyclic graph can not contain a negative cycle.
+    /// Returns [CycleError] if the graph contains a cycle.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n + m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn shortest_path_dag(&self, start: Handle) -> Result<Vec<Option<W>>, CycleError> {
+        let order = self.topological_sort()?;
+
+        let mut dist = vec![None; self.size()];
+        dist[start.0] = Some(W::zero());
+        for Handle(from) in order {
+            let Some(from_dist) = dist[from] else {
+                continue;
+            };
+            for connection in &self.edges[from] {
+                let to = connection.pointing_to().0;
+                let candidate = from_dist + connection.weight();
+                if dist[to].is_none_or(|to_dist| candidate < to_dist) {
+                    dist[to] = Some(candidate);
+                }
+            }
+        }
+
+        Ok(dist)
+    }
+
+    /// Finds the shortest distance from `start` to every vertex along with the predecessor
+    /// of every vertex on a shortest path from `start`, using Dijkstra's algorithm.
+    /// Vertices that are not reachable from `start` have neither a distance nor a predecessor.
+    ///
+    /// All edge weights must be non-negative, otherwise the result may not be optimal.
+    ///
+    /// ```
+    /// # use comtesse::weighted::Weighted;
+    /// let mut graph: Weighted<_, u32> = ('a'..='c').collect();
+    /// graph.construct_edges_from(|&u, &v| match (u, v) {
+    ///     ('a', 'b') => Some(1),
+    ///     ('b', 'c') => Some(1),
+    ///     ('a', 'c') => Some(5),
+    ///     _ => None,
+    /// });
+    /// let a = graph.get_vertex('a').unwrap();
+    /// let b = graph.get_vertex('b').unwrap();
+    ///
+    /// let (dist, pred) = graph.dijkstra_all(a);
+    /// assert_eq!(dist, [Some(0), Some(1), Some(2)]);
+    /// assert_eq!(pred, [None, Some(a), Some(b)]);
+    /// ```
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O((n + m) log n)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn dijkstra_all(&self, start: Handle) -> (Vec<Option<W>>, Vec<Option<Handle>>) {
+        let mut dist = vec![None; self.size()];
+        let mut pred = vec![None; self.size()];
+        dist[start.0] = Some(W::zero());
+
+        let mut queue = BinaryHeap::new();
+        queue.push(MinScored(W::zero(), start.0));
+        while let Some(MinScored(from_dist, from)) = queue.pop() {
+            if dist[from].is_some_and(|dist| dist < from_dist) {
+                continue;
+            }
+
+            for connection in &self.edges[from] {
+                let to = connection.pointing_to().0;
+                let candidate = from_dist + connection.weight();
+                if dist[to].is_none_or(|to_dist| candidate < to_dist) {
+                    dist[to] = Some(candidate);
+                    pred[to] = Some(Handle(from));
+                    queue.push(MinScored(candidate, to));
+                }
+            }
+        }
+
+        (dist, pred)
+    }
+
+    /// Computes the shortest path distances between all pairs of vertices by running [Weighted::dijkstra_all]
+    /// from every vertex. `distances[u][v]` is the distance from `u` to `v` or `None` if `v` is not reachable from `u`.
+    ///
+    /// All edge weights must be non-negative. With the `rayon` feature, [Weighted::par_all_pairs_dijkstra]
+    /// computes the same distances in parallel.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n (n + m) log n)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn all_pairs_dijkstra(&self) -> Vec<Vec<Option<W>>> {
+        (0..self.size())
+            .map(|start| self.dijkstra_all(Handle(start)).0)
+            .collect()
+    }
+
+    /// Computes the same distances as [Weighted::all_pairs_dijkstra], running the searches from the
+    /// different vertices in parallel using rayon.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n (n + m) log n)` where `n` is the number of vertices and `m` is the number of edges,
+    /// divided among the threads of the rayon thread pool
+    #[cfg(feature = "rayon")]
+    pub fn par_all_pairs_dijkstra(&self) -> Vec<Vec<Option<W>>>
+    where
+        V: Sync,
+        W: Send + Sync,
+    {
+        use rayon::prelude::*;
+
+        (0..self.size())
+            .into_par_iter()
+            .map(|start| self.dijkstra_all(Handle(start)).0)
+            .collect()
+    }
+
+    /// Finds the shortest path from `start` to `end` and its total weight using Dijkstra's algorithm,
+    /// or returns `None` if `end` is not reachable from `start`.
+    ///
+    /// All edge weights must be non-negative, otherwise the result may not be optimal.
+    /// To find paths to many targets, use [Weighted::dijkstra_all] instead.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O((n + m) log n)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn shortest_path_dijkstra(&self, start: Handle, end: Handle) -> Option<(Vec<Handle>, W)> {
+        let (dist, pred) = self.dijkstra_all(start);
+        Some((path_from_predecessors(&pred, start, end)?, dist[end.0]?))
+    }
+
+    /// Returns the weighted eccentricity of `vertex`, i.e. the largest total weight of a shortest path
+    /// from `vertex` to any other vertex.
+    ///
+    /// Edges are directed. If some vertex is not reachable from `vertex`, the eccentricity is infinite
+    /// and `None` is returned. All edge weights must be non-negative.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O((n + m) log n)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn weighted_eccentricity(&self, vertex: Handle) -> Option<W> {
+        let (dist, _) = self.dijkstra_all(vertex);
+        dist.into_iter().try_fold(W::zero(), |farthest, dist| {
+            let dist = dist?;
+            Some(if dist > farthest { dist } else { farthest })
+        })
+    }
+
+    /// Returns the weighted diameter of the graph, i.e. the largest [weighted eccentricity](Weighted::weighted_eccentricity)
+    /// of any vertex.
+    ///
+    /// Returns `None` if the graph is empty or if some vertex can not reach every other vertex,
+    /// so for graphs that are not strongly connected the diameter is considered infinite.
+    /// All edge weights must be non-negative.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n (n + m) log n)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn weighted_diameter(&self) -> Option<W> {
+        let mut diameter = None;
+        for vertex in (0..self.size()).map(Handle) {
+            let eccentricity = self.weighted_eccentricity(vertex)?;
+            if diameter.is_none_or(|diameter| eccentricity > diameter) {
+                diameter = Some(eccentricity);
+            }
+        }
+        diameter
+    }
+
+    /// Finds a path from `start` to `end` whose largest edge weight is as small as possible,
+    /// returning the path and that largest weight, or `None` if `end` is not reachable from `start`.
+    ///
+    /// Unlike [Weighted::shortest_path_dijkstra], this minimizes the bottleneck of the path instead of its total weight.
+    /// If `start` and `end` are the same vertex, the path only consists of `start` and its bottleneck is zero.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O((n + m) log n)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn minimum_bottleneck_path(&self, start: Handle, end: Handle) -> Option<(Vec<Handle>, W)> {
+        if start == end {
+            return Some((vec![start], W::zero()));
+        }
+
+        let mut bottleneck = vec![None; self.size()];
+        let mut pred = vec![None; self.size()];
+        let mut done = vec![false; self.size()];
+        done[start.0] = true;
+
+        let mut queue = BinaryHeap::new();
+        for connection in &self.edges[start.0] {
+            let to = connection.pointing_to().0;
+            let weight = connection.weight();
+            if to != start.0 && bottleneck[to].is_none_or(|best| weight < best) {
+                bottleneck[to] = Some(weight);
+                pred[to] = Some(start);
+                queue.push(MinScored(weight, to));
+            }
+        }
+
+        while let Some(MinScored(from_bottleneck, from)) = queue.pop() {
+            if done[from] {
+                continue;
+            }
+            done[from] = true;
+            if from == end.0 {
+                break;
+            }
+
+            for connection in &self.edges[from] {
+                let to = connection.pointing_to().0;
+                let weight = connection.weight();
+                let candidate = if weight > from_bottleneck {
+                    weight
+                } else {
+                    from_bottleneck
+                };
+                if !done[to] && bottleneck[to].is_none_or(|best| candidate < best) {
+                    bottleneck[to] = Some(candidate);
+                    pred[to] = Some(Handle(from));
+                    queue.push(MinScored(candidate, to));
+                }
+            }
+        }
+
+        Some((
+            path_from_predecessors(&pred, start, end)?,
+            bottleneck[end.0]?,
+        ))
+    }
+
+    /// Finds the shortest path from `start` to `end` and its total weight, or returns `None` if `end` is not
+    /// reachable from `start` or a negative cycle reachable from `start` prevents shortest paths from existing.
+    ///
+    /// This uses [Weighted::shortest_path_dijkstra] if all weights are non-negative (see [Weighted::has_negative_weights])
+    /// and the Bellman-Ford algorithm otherwise. Use [Weighted::find_negative_cycle] to tell the two reasons for `None` apart.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O((n + m) log n)` without negative weights and `O(n * m)` otherwise,
+    /// where `n` is the number of vertices and `m` is the number of edges
+    pub fn shortest_path(&self, start: Handle, end: Handle) -> Option<(Vec<Handle>, W)> {
+        if !self.has_negative_weights() {
+            return self.shortest_path_dijkstra(start, end);
+        }
+
+        let (dist, pred, None) = self.bellman_ford_core(start) else {
+            return None;
+        };
+        let pred: Vec<_> = pred.into_iter().map(|pred| pred.map(Handle)).collect();
+        Some((path_from_predecessors(&pred, start, end)?, dist[end.0]?))
+    }
+
+    /// Finds the cheapest path from `start` to `end` and its total cost, where the cost of a path is the sum of
+    /// its edge weights plus `node_cost(v)` for every vertex `v` the path passes through.
+    ///
+    /// Only the intermediate vertices of the path are charged, not `start` and `end` themselves, so a vertex
+    /// with a high cost is only avoided as a stopover. Returns `None` if `end` is not reachable from `start`.
+    /// All edge weights and node costs must be non-negative.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O((n + m) log n)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn shortest_path_with_node_cost(
+        &self,
+        start: Handle,
+        end: Handle,
+        node_cost: impl Fn(Handle) -> W,
+    ) -> Option<(Vec<Handle>, W)> {
+        let mut dist = vec![None; self.size()];
+        let mut pred = vec![None; self.size()];
+        dist[start.0] = Some(W::zero());
+
+        let mut queue = BinaryHeap::new();
+        queue.push(MinScored(W::zero(), start.0));
+        while let Some(MinScored(from_dist, from)) = queue.pop() {
+            if dist[from].is_some_and(|dist| dist < from_dist) {
+                continue;
+            }
+            if from == end.0 {
+                break;
+            }
+
+            let leave_cost = if from == start.0 {
+                from_dist
+            } else {
+                from_dist + node_cost(Handle(from))
+            };
+            for connection in &self.edges[from] {
+                let to = connection.pointing_to().0;
+                let candidate = leave_cost + connection.weight();
+                if dist[to].is_none_or(|to_dist| candidate < to_dist) {
+                    dist[to] = Some(candidate);
+                    pred[to] = Some(Handle(from));
+                    queue.push(MinScored(candidate, to));
+                }
+            }
+        }
+
+        Some((path_from_predecessors(&pred, start, end)?, dist[end.0]?))
+    }
+
+    /// Finds the cheapest path from `start` to `end` whose total weight is strictly larger than that of a shortest path,
+    /// returning the path and its total weight, or `None` if no such path exists.
+    ///
+    /// Paths with the same weight as a shortest path are not considered. The returned path may visit a vertex
+    /// more than once, for example by going back and forth along an undirected edge.
+    /// All edge weights must be non-negative.
+    ///
+    /// ```
+    /// # use comtesse::weighted::Weighted;
+    /// let mut graph: Weighted<_, u32> = ('a'..='c').collect();
+    /// graph.construct_edges_from(|&u, &v| match (u, v) {
+    ///     ('a', 'b') | ('b', 'c') => Some(1),
+    ///     ('a', 'c') => Some(5),
+    ///     _ => None,
+    /// });
+    /// let a = graph.get_vertex('a').unwrap();
+    /// let c = graph.get_vertex('c').unwrap();
+    ///
+    /// let (path, weight) = graph.second_shortest_path(a, c).unwrap();
+    /// assert_eq!(path, [a, c]);
+    /// assert_eq!(weight, 5);
+    /// ```
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O((n + m) log n)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn second_shortest_path(&self, start: Handle, end: Handle) -> Option<(Vec<Handle>, W)> {
+        // the state `2 * v` is the shortest way to reach `v`, `2 * v + 1` the second shortest one
+        let mut dist = vec![None; 2 * self.size()];
+        let mut pred = vec![None; 2 * self.size()];
+        dist[2 * start.0] = Some(W::zero());
+
+        let mut queue = BinaryHeap::new();
+        queue.push(MinScored(W::zero(), 2 * start.0));
+        while let Some(MinScored(from_dist, state)) = queue.pop() {
+            if dist[state].is_some_and(|dist| dist < from_dist) {
+                continue;
+            }
+
+            for connection in &self.edges[state / 2] {
+                let (best, second) = (
+                    2 * connection.pointing_to().0,
+                    2 * connection.pointing_to().0 + 1,
+                );
+                let candidate = from_dist + connection.weight();
+                if dist[best].is_none_or(|best_dist| candidate < best_dist) {
+                    if let Some(previous) = dist[best] {
+                        dist[second] = Some(previous);
+                        pred[second] = pred[best];
+                        queue.push(MinScored(previous, second));
+                    }
+                    dist[best] = Some(candidate);
+                    pred[best] = Some(state);
+                    queue.push(MinScored(candidate, best));
+                } else if dist[best].is_some_and(|best_dist| best_dist < candidate)
+                    && dist[second].is_none_or(|second_dist| candidate < second_dist)
+                {
+                    dist[second] = Some(candidate);
+                    pred[second] = Some(state);
+                    queue.push(MinScored(candidate, second));
+                }
+            }
+        }
+
+        let weight = dist[2 * end.0 + 1]?;
+        let mut path = vec![end];
+        let mut cur = 2 * end.0 + 1;
+        while let Some(prev) = pred[cur] {
+            cur = prev;
+            path.push(Handle(cur / 2));
+        }
+        path.reverse();
+        Some((path, weight))
+    }
+
+    /// Finds a cycle with the smallest mean edge weight using Karp's algorithm,
+    /// returning the cycle and its mean weight, or `None` if the graph is acyclic.
+    ///
+    /// The cycle is returned as a sequence of vertices where each vertex has an edge to the next one
+    /// and the last vertex has an edge back to the first one. For integer weights the returned mean
+    /// is rounded like integer division, though the cycle itself is chosen using exact comparisons.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n (n + m))` and uses `O(n^2)` memory,
+    /// where `n` is the number of vertices and `m` is the number of edges
+    pub fn minimum_mean_cycle(&self) -> Option<(Vec<Handle>, W)> {
+        let n = self.size();
+
+        // dist[k][v] is the weight of the lightest walk with exactly k edges ending in v
+        let mut dist = vec![vec![None; n]; n + 1];
+        let mut pred = vec![vec![0; n]; n + 1];
+        dist[0] = vec![Some(W::zero()); n];
+        for k in 1..=n {
+            for from in 0..n {
+                let Some(from_dist) = dist[k - 1][from] else {
+                    continue;
+                };
+                for connection in &self.edges[from] {
+                    let to = connection.pointing_to().0;
+                    let candidate = from_dist + connection.weight();
+                    if dist[k][to].is_none_or(|to_dist| candidate < to_dist) {
+                        dist[k][to] = Some(candidate);
+                        pred[k][to] = from;
+                    }
+                }
+            }
+        }
+
+        // counts[k] is k as a weight, i.e. `1 + 1 + ... + 1`
+        let counts: Vec<W> =
+            std::iter::successors(Some(W::zero()), |&count| Some(count + W::one()))
+                .take(n + 1)
+                .collect();
+        // a / p < b / q for positive p and q
+        let less = |(a, p): (W, usize), (b, q): (W, usize)| a * counts[q] < b * counts[p];
+
+        let mut best: Option<((W, usize), usize)> = None;
+        for (vertex, &walk) in dist[n].iter().enumerate() {
+            let Some(walk) = walk else {
+                continue;
+            };
+
+            let worst = (0..n)
+                .filter_map(|k| Some((walk - dist[k][vertex]?, n - k)))
+                .reduce(|worst, mean| if less(worst, mean) { mean } else { worst })
+                .expect("dist[0] is always set");
+
+            if best.is_none_or(|(best, _)| less(worst, best)) {
+                best = Some((worst, vertex));
+            }
+        }
+        let ((sum, count), end) = best?;
+
+        // the walk of length n ending in `end` contains a minimum mean cycle
+        let mut position = vec![None; n];
+        let mut walk = vec![];
+        let mut cur = end;
+        for k in (0..=n).rev() {
+            if let Some(start) = position[cur] {
+                let mut cycle: Vec<_> = walk[start + 1..].iter().copied().map(Handle).collect();
+                cycle.push(Handle(cur));
+                cycle.reverse();
+                return Some((cycle, sum / counts[count]));
+            }
+            position[cur] = Some(walk.len());
+            walk.push(cur);
+            if k > 0 {
+                cur = pred[k][cur];
+            }
+        }
+
+        unreachable!("a walk with n edges must contain a cycle")
+    }
+
+    /// Returns the weight of the lightest edge from `from` to `to`
+    fn lightest_edge(&self, from: usize, to: usize) -> Option<W> {
+        self.edges[from]
+            .iter()
+            .filter(|connection| connection.pointing_to().0 == to)
+            .map(|connection| connection.weight())
+            .min_by(compare_weights)
+    }
+
+    /// Computes the value of a maximum flow from `source` to `sink` using the Edmonds-Karp algorithm,
+    /// where the weight of every edge is its capacity.
+    ///
+    /// All capacities must be non-negative. Parallel edges add up their capacities and self loops are ignored.
+    /// To compute a flow between several sources and sinks, see [Weighted::to_flow_network].
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n * m^2)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn max_flow(&self, source: Handle, sink: Handle) -> W {
+        if source == sink {
+            return W::zero();
+        }
+
+        // residual edges as (target, remaining capacity), where edge `i ^ 1` is the reverse of edge `i`
+        let mut residual = vec![];
+        let mut outgoing = vec![vec![]; self.size()];
+        for (from, connections) in self.edges.iter().enumerate() {
+            for connection in connections {
+                let to = connection.pointing_to().0;
+                if from == to {
+                    continue;
+                }
+                outgoing[from].push(residual.len());
+                residual.push((to, connection.weight()));
+                outgoing[to].push(residual.len());
+                residual.push((from, W::zero()));
+            }
+        }
+
+        let mut flow = W::zero();
+        let mut via = vec![None; self.size()];
+        loop {
+            via.fill(None);
+            let mut queue = VecDeque::from([source.0]);
+            while let Some(front) = queue.pop_front() {
+                for &edge in &outgoing[front] {
+                    let (to, capacity) = residual[edge];
+                    if to != source.0 && via[to].is_none() && capacity > W::zero() {
+                        via[to] = Some(edge);
+                        queue.push_back(to);
+                    }
+                }
+            }
+            if via[sink.0].is_none() {
+                return flow;
+            }
+
+            let mut bottleneck = None;
+            let mut vertex = sink.0;
+            while let Some(edge) = via[vertex] {
+                let capacity = residual[edge].1;
+                if bottleneck.is_none_or(|bottleneck| capacity < bottleneck) {
+                    bottleneck = Some(capacity);
+                }
+                vertex = residual[edge ^ 1].0;
+            }
+            let bottleneck = bottleneck.expect("the sink is reached by at least one edge");
+
+            let mut vertex = sink.0;
+            while let Some(edge) = via[vertex] {
+                residual[edge].1 = residual[edge].1 - bottleneck;
+                residual[edge ^ 1].1 = residual[edge ^ 1].1 + bottleneck;
+                vertex = residual[edge ^ 1].0;
+            }
+            flow = flow + bottleneck;
+        }
+    }
+
+    /// Returns a copy of the graph with an added super source that has an edge to every vertex in `sources`
+    /// and an added super sink with an edge from every vertex in `sinks`, together with the handles of the
+    /// super source and the super sink.
+    ///
+    /// A maximum flow from the super source to the super sink (see [Weighted::max_flow]) is a maximum flow
+    /// from all `sources` to all `sinks`. The new edges have a capacity larger than the total capacity of
+    /// all edges, so they never limit the flow. The new vertices hold the default value of `V`.
+    pub fn to_flow_network(
+        &self,
+        sources: &[Handle],
+        sinks: &[Handle],
+    ) -> (Weighted<V, W>, Handle, Handle)
+    where
+        V: Clone + Default,
+    {
+        let unlimited = self.total_weight() + W::one();
+
+        let mut network = self.clone();
+        let super_source = network.add_vertex(V::default());
+        let super_sink = network.add_vertex(V::default());
+        for &source in sources {
+            network.add_edge(super_source, source, unlimited);
+        }
+        for &sink in sinks {
+            network.add_edge(sink, super_sink, unlimited);
+        }
+
+        (network, super_source, super_sink)
+    }
+
+    /// Runs Kruskal's algorithm, calling `add` with every edge of a minimum spanning forest
+    fn kruskal(&self, mut add: impl FnMut(usize, usize, W)) {
+        let mut edges: Vec<_> = self
+            .edges
+            .iter()
+            .enumerate()
+            .flat_map(|(from, connections)| {
+                connections
+                    .iter()
+                    .map(move |connection| (connection.weight(), from, connection.pointing_to().0))
+            })
+            .collect();
+        edges.sort_by(|a, b| compare_weights(&a.0, &b.0));
+
+        let mut sets = DisjointSet::new(self.size());
+        for (weight, from, to) in edges {
+            if sets.union(from, to) {
+                add(from, to, weight);
+            }
+        }
+    }
+
+    /// Finds a minimum spanning forest using Kruskal's algorithm, i.e. a set of edges of minimum
+    /// total weight that connects every pair of vertices that is connected in the graph.
+    ///
+    /// Edges are treated as undirected. The returned graph has the same vertices (and handles)
+    /// as this graph and contains every edge of the forest in both directions.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n + m log m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn minimum_spanning_tree(&self) -> Weighted<V, W>
+    where
+        V: Clone,
+    {
+        let mut tree: Weighted<V, W> = self.vertices.iter().cloned().collect();
+        self.kruskal(|from, to, weight| {
+            tree.add_edge(Handle(from), Handle(to), weight);
+            tree.add_edge(Handle(to), Handle(from), weight);
+        });
+        tree
+    }
+
+    /// Returns the total weight of a [minimum spanning forest](Weighted::minimum_spanning_tree)
+    /// without building it.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n + m log m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn mst_weight(&self) -> W {
+        let mut total = W::zero();
+        self.kruskal(|_, _, weight| total = total + weight);
+        total
+    }
+
+    /// Returns the connected components of the graph when only the edges with a weight of at least `cutoff` are kept.
+    ///
+    /// Edges are treated as undirected and vertices without such an edge form a component on their own.
+    /// Every component is sorted by handle and the components are ordered by their smallest handle.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O((n + m) α(n))` where `n` is the number of vertices,
+    /// `m` is the number of edges and `α` is the inverse Ackermann function
+    pub fn components_above_threshold(&self, cutoff: W) -> Vec<Vec<Handle>> {
+        let mut sets = DisjointSet::new(self.size());
+        for (from, connections) in self.edges.iter().enumerate() {
+            for connection in connections {
+                if connection.weight() >= cutoff {
+                    sets.union(from, connection.pointing_to().0);
+                }
+            }
+        }
+
+        let mut index_of_root = vec![None; self.size()];
+        let mut components: Vec<Vec<Handle>> = vec![];
+        for vertex in 0..self.size() {
+            let root = sets.find(vertex);
+            let index = *index_of_root[root].get_or_insert_with(|| {
+                components.push(vec![]);
+                components.len() - 1
+            });
+            components[index].push(Handle(vertex));
+        }
+        components
+    }
+
+    /// Approximates a minimum Steiner tree, i.e. a tree of minimum total weight connecting all `terminals`,
+    /// possibly using other vertices along the way.
+    ///
+    /// This is the classic 2-approximation: it joins the terminals along the shortest paths chosen by a
+    /// minimum spanning tree of the metric closure, so the result weighs at most twice as much as the optimum.
+    /// The graph is treated as undirected, so every undirected edge should be stored in both directions.
+    ///
+    /// The returned graph has the same vertices (and handles) as this graph and contains every edge of the
+    /// tree in both directions. Terminals that can not be reached from each other end up in separate trees.
+    /// All edge weights must be non-negative.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(t (n + m) log n)` where `t` is the number of terminals,
+    /// `n` is the number of vertices and `m` is the number of edges
+    pub fn steiner_tree_approx(&self, terminals: &[Handle]) -> Weighted<V, W>
+    where
+        V: Clone,
+    {
+        let shortest: Vec<_> = terminals
+            .iter()
+            .map(|&terminal| self.dijkstra_all(terminal))
+            .collect();
+
+        // Prim's algorithm on the metric closure of the terminals
+        let mut in_tree = vec![false; terminals.len()];
+        let mut best: Vec<Option<(W, usize)>> = vec![None; terminals.len()];
+        let mut closure_edges = vec![];
+        while let Some(next) =
+            (0..terminals.len())
+                .filter(|&i| !in_tree[i])
+                .min_by(|&a, &b| match (best[a], best[b]) {
+                    (Some((a, _)), Some((b, _))) => compare_weights(&a, &b),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                })
+        {
+            in_tree[next] = true;
+            if let Some((_, from)) = best[next] {
+                closure_edges.push((from, next));
+            }
+
+            for other in (0..terminals.len()).filter(|&i| !in_tree[i]) {
+                let Some(dist) = shortest[next].0[terminals[other].0] else {
+                    continue;
+                };
+                if best[other].is_none_or(|(best, _)| dist < best) {
+                    best[other] = Some((dist, next));
+                }
+            }
+        }
+
+        // union of the shortest paths between terminals joined in the closure
+        let mut union = vec![vec![]; self.size()];
+        for (from, to) in closure_edges {
+            let path = path_from_predecessors(&shortest[from].1, terminals[from], terminals[to])
+                .expect("only reachable terminals are joined");
+            for pair in path.windows(2) {
+                let (u, v) = (pair[0].0, pair[1].0);
+                if !union[u].iter().any(|&(neighbor, _)| neighbor == v) {
+                    let weight = self.lightest_edge(u, v).expect("paths follow edges");
+                    union[u].push((v, weight));
+                    union[v].push((u, weight));
+                }
+            }
+        }
+
+        // the union may contain cycles, so only keep a spanning forest of it
+        let mut tree = vec![vec![]; self.size()];
+        let mut seen = vec![false; self.size()];
+        for &Handle(root) in terminals {
+            if seen[root] {
+                continue;
+            }
+            seen[root] = true;
+            let mut stack = vec![root];
+            while let Some(top) = stack.pop() {
+                for &(neighbor, weight) in &union[top] {
+                    if !seen[neighbor] {
+                        seen[neighbor] = true;
+                        tree[top].push((neighbor, weight));
+                        tree[neighbor].push((top, weight));
+                        stack.push(neighbor);
+                    }
+                }
+            }
+        }
+
+        // leaves that are not terminals are unnecessary
+        let mut is_terminal = vec![false; self.size()];
+        for &Handle(terminal) in terminals {
+            is_terminal[terminal] = true;
+        }
+        let mut leaves: Vec<_> = (0..self.size())
+            .filter(|&v| tree[v].len() == 1 && !is_terminal[v])
+            .collect();
+        while let Some(leaf) = leaves.pop() {
+            let Some((neighbor, _)) = tree[leaf].pop() else {
+                continue;
+            };
+            tree[neighbor].retain(|&(other, _)| other != leaf);
+            if tree[neighbor].len() == 1 && !is_terminal[neighbor] {
+                leaves.push(neighbor);
+            }
+        }
+
+        let mut result: Weighted<V, W> = self.vertices.iter().cloned().collect();
+        for (from, neighbors) in tree.into_iter().enumerate() {
+            for (to, weight) in neighbors {
+                result.add_edge(Handle(from), Handle(to), weight);
+            }
+        }
+        result
+    }
+
+    /// Returns all vertices that can be reached from `start` with a total path weight of at most `budget`,
+    /// sorted by handle. `start` itself is always included.
+    ///
+    /// This runs Dijkstra's algorithm but stops expanding paths as soon as they exceed the budget,
+    /// so only the part of the graph within the budget is explored. All edge weights must be non-negative.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O((n' + m') log n')` where `n'` and `m'` are the numbers
+    /// of vertices and edges within the budget
+    pub fn within_cost(&self, start: Handle, budget: W) -> Vec<Handle> {
+        let mut dist = vec![None; self.size()];
+        dist[start.0] = Some(W::zero());
+
+        let mut queue = BinaryHeap::new();
+        queue.push(MinScored(W::zero(), start.0));
+        while let Some(MinScored(from_dist, from)) = queue.pop() {
+            if dist[from].is_some_and(|dist| dist < from_dist) {
+                continue;
+            }
+
+            for connection in &self.edges[from] {
+                let to = connection.pointing_to().0;
+                let candidate = from_dist + connection.weight();
+                if candidate <= budget && dist[to].is_none_or(|to_dist| candidate < to_dist) {
+                    dist[to] = Some(candidate);
+                    queue.push(MinScored(candidate, to));
+                }
+            }
+        }
+
+        (0..self.size())
+            .filter(|&vertex| dist[vertex].is_some())
+            .map(Handle)
+            .collect()
+    }
+
+    /// Finds a shortest closed walk that traverses every edge at least once (the route inspection problem),
+    /// returning the walk and its total weight, or `None` if the graph is not connected.
+    ///
+    /// The graph is treated as undirected, so every edge has to be stored in both directions
+    /// while self loops are stored once. The walk starts and ends at the first vertex, with every vertex
+    /// in the walk being connected to the next one. All edge weights must be non-negative.
+    ///
+    /// The vertices of odd degree are paired up by a matching on their shortest path distances.
+    /// The paths between matched vertices are traversed twice and the resulting Eulerian multigraph
+    /// is walked using Hierholzer's algorithm.
+    ///
+    /// With at most 16 vertices of odd degree, a minimum weight perfect matching is used and the walk is optimal.
+    /// With more, the closest unmatched vertices are paired greedily instead, so the walk only approximates the optimum:
+    /// the weight added by the greedy matching is at most `O(k^0.59)` times that of a minimum weight matching.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(k (n + m) log n + k^2 log k)` where `n` is the number of vertices,
+    /// `m` is the number of edges and `k` is the number of vertices of odd degree,
+    /// plus `O(2^k k)` for the exact matching if `k <= 16`
+    pub fn chinese_postman(&self) -> Option<(Vec<Handle>, W)> {
+        if self.size() == 0 {
+            return Some((vec![], W::zero()));
+        }
+        if self
+            .connected_components()
+            .iter()
+            .any(|&component| component != 0)
+        {
+            return None;
+        }
+
+        let mut edges = vec![];
+        let mut total = W::zero();
+        for (from, connections) in self.edges.iter().enumerate() {
+            for connection in connections {
+                let to = connection.pointing_to().0;
+                if from <= to {
+                    edges.push((from, to));
+                    total = total + connection.weight();
+                }
+            }
+        }
+
+        let mut degree = vec![0usize; self.size()];
+        for &(from, to) in &edges {
+            degree[from] += 1;
+            degree[to] += 1;
+        }
+        let odd: Vec<_> = (0..self.size()).filter(|&v| degree[v] % 2 == 1).collect();
+        let searches: Vec<_> = odd.iter().map(|&v| self.dijkstra_all(Handle(v))).collect();
+
+        let between: Vec<Vec<_>> = searches
+            .iter()
+            .map(|(dist, _)| odd.iter().map(|&v| dist[v]).collect())
+            .collect();
+        let pairs = if odd.len() <= EXACT_MATCHING_LIMIT {
+            exact_matching(&between)?
+        } else {
+            greedy_matching(&between)?
+        };
+
+        for (first, second) in pairs {
+            total = total + between[first][second]?;
+            let (_, pred) = &searches[first];
+            let path = path_from_predecessors(pred, Handle(odd[first]), Handle(odd[second]))?;
+            edges.extend(path.windows(2).map(|pair| (pair[0].0, pair[1].0)));
+        }
+
+        let mut incident = vec![vec![]; self.size()];
+        for (id, &(from, to)) in edges.iter().enumerate() {
+            incident[from].push((to, id));
+            if from != to {
+                incident[to].push((from, id));
+            }
+        }
+
+        let mut used = vec![false; edges.len()];
+        let mut next = vec![0; self.size()];
+        let mut walk = vec![];
+        let mut stack = vec![0];
+        while let Some(&vertex) = stack.last() {
+            while incident[vertex]
+                .get(next[vertex])
+                .is_some_and(|&(_, id)| used[id])
+            {
+                next[vertex] += 1;
+            }
+            match incident[vertex].get(next[vertex]) {
+                Some(&(neighbor, id)) => {
+                    used[id] = true;
+                    stack.push(neighbor);
+                }
+                None => {
+                    stack.pop();
+                    walk.push(Handle(vertex));
+                }
+            }
+        }
+        walk.reverse();
+
+        Some((walk, total))
+    }
+
+    /// Finds a perfect matching between the vertices in `left` and the vertices in `right` with the smallest total weight
+    /// (the assignment problem), returning the matched pairs `(l, r)` in the order of `left` and their total weight.
+    ///
+    /// Only edges going from a vertex in `left` to a vertex in `right` are used, of multiple such edges the lightest one.
+    /// The two sides must not share any vertices. Returns `None` if `left` and `right` have different lengths
+    /// or no perfect matching exists.
+    ///
+    /// This uses the Hungarian algorithm, which also works for unsigned weights.
+    ///
+    /// ```
+    /// # use comtesse::weighted::Weighted;
+    /// let mut graph: Weighted<_, u32> = ["alice", "bob", "build", "test"].into_iter().collect();
+    /// let [alice, bob, build, test] = ["alice", "bob", "build", "test"].map(|v| graph.get_vertex(v).unwrap());
+    /// graph.add_edge(alice, build, 2);
+    /// graph.add_edge(alice, test, 3);
+    /// graph.add_edge(bob, build, 1);
+    /// graph.add_edge(bob, test, 4);
+    ///
+    /// let (pairs, cost) = graph.min_cost_matching(&[alice, bob], &[build, test]).unwrap();
+    /// assert_eq!(pairs, [(alice, test), (bob, build)]);
+    /// assert_eq!(cost, 4);
+    /// ```
+    ///
+    /// ## Panics
+    ///
+    /// Panics if any of the handles is not a vertex of the graph
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(k^3 + n + m)` where `k` is the number of vertices on each side,
+    /// `n` is the number of vertices and `m` is the number of edges
+    pub fn min_cost_matching(
+        &self,
+        left: &[Handle],
+        right: &[Handle],
+    ) -> Option<(Vec<(Handle, Handle)>, W)> {
+        let k = left.len();
+        if right.len() != k {
+            return None;
+        }
+
+        let mut column = vec![None; self.size()];
+        for (j, &Handle(vertex)) in right.iter().enumerate() {
+            column[vertex] = Some(j + 1);
+        }
+        // cost[i][j] is the lightest edge from left[i - 1] to right[j - 1], row and column 0 are unused
+        let mut cost = vec![vec![None; k + 1]; k + 1];
+        for (i, &Handle(vertex)) in left.iter().enumerate() {
+            for connection in &self.edges[vertex] {
+                if let Some(j) = column[connection.pointing_to().0] {
+                    let weight = connection.weight();
+                    if cost[i + 1][j].is_none_or(|best| weight < best) {
+                        cost[i + 1][j] = Some(weight);
+                    }
+                }
+            }
+        }
+
+        // the reduced cost of an edge is `cost[i][j] + v[j] - u[i]`, which never becomes negative,
+        // so the potentials only grow and the computation works for unsigned weights
+        let mut u = vec![W::zero(); k + 1];
+        let mut v = vec![W::zero(); k + 1];
+        // row[j] is the row matched to column j, or 0 if it is unmatched
+        let mut row = vec![0; k + 1];
+        let mut way = vec![0; k + 1];
+        for i in 1..=k {
+            row[0] = i;
+            let mut current = 0;
+            let mut min_reduced: Vec<Option<W>> = vec![None; k + 1];
+            let mut used = vec![false; k + 1];
+            while row[current] != 0 {
+                used[current] = true;
+                let from = row[current];
+                let mut delta = None;
+                let mut next = 0;
+                for j in 1..=k {
+                    if used[j] {
+                        continue;
+                    }
+                    if let Some(weight) = cost[from][j] {
+                        let reduced = weight + v[j] - u[from];
+                        if min_reduced[j].is_none_or(|min| reduced < min) {
+                            min_reduced[j] = Some(reduced);
+                            way[j] = current;
+                        }
+                    }
+                    if let Some(min) = min_reduced[j] {
+                        if delta.is_none_or(|delta| min < delta) {
+                            delta = Some(min);
+                            next = j;
+                        }
+                    }
+                }
+
+                let delta = delta?;
+                for j in 0..=k {
+                    if used[j] {
+                        u[row[j]] = u[row[j]] + delta;
+                        v[j] = v[j] + delta;
+                    } else if let Some(min) = min_reduced[j] {
+                        min_reduced[j] = Some(min - delta);
+                    }
+                }
+                current = next;
+            }
+
+            while current != 0 {
+                let previous = way[current];
+                row[current] = row[previous];
+                current = previous;
+            }
+        }
+
+        let mut pairs = vec![(Handle(0), Handle(0)); k];
+        let mut total = W::zero();
+        for j in 1..=k {
+            let i = row[j];
+            pairs[i - 1] = (left[i - 1], right[j - 1]);
+            total = total + cost[i][j]?;
+        }
+        Some((pairs, total))
+    }
+}
+
+impl<V> Weighted<V, f64> {
+    /// Finds the most reliable path from `start` to `end`, where the weight of every edge is the probability
+    /// of it working and the reliability of a path is the product of its edge weights.
+    /// Returns the path and its reliability, or `None` if `end` is not reachable from `start`.
+    ///
+    /// All edge weights must be in `[0, 1]`, so that the reliability never grows along a path.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O((n + m) log n)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn most_reliable_path(&self, start: Handle, end: Handle) -> Option<(Vec<Handle>, f64)> {
+        let mut reliability = vec![None; self.size()];
+        let mut pred = vec![None; self.size()];
+        reliability[start.0] = Some(1.0);
+
+        // negated, so that the most reliable vertex is popped first
+        let mut queue = BinaryHeap::new();
+        queue.push(MinScored(-1.0, start.0));
+        while let Some(MinScored(negated, from)) = queue.pop() {
+            let from_reliability = -negated;
+            if reliability[from].is_some_and(|reliability| reliability > from_reliability) {
+                continue;
+            }
+            if from == end.0 {
+                break;
+            }
+
+            for connection in &self.edges[from] {
+                let to = connection.pointing_to().0;
+                let candidate = from_reliability * connection.weight();
+                if reliability[to].is_none_or(|to_reliability| candidate > to_reliability) {
+                    reliability[to] = Some(candidate);
+                    pred[to] = Some(Handle(from));
+                    queue.push(MinScored(-candidate, to));
+                }
+            }
+        }
+
+        Some((
+            path_from_predecessors(&pred, start, end)?,
+            reliability[end.0]?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::path_from_predecessors;
+    use crate::{graph::Handle, weighted::Weighted, CycleError, HasEdge, NegativeCycle};
+
+    #[test]
+    fn bellman_ford() {
+        let mut graph: Weighted<_, i32> = ('a'..='e').collect();
+        graph.construct_edges_from(|&from, &to| match (from, to) {
+            ('a', 'b') => Some(4),
+            ('a', 'c') => Some(2),
+            ('c', 'b') => Some(-1),
+            ('b', 'd') => Some(3),
+            ('d', 'e') => Some(-2),
+            _ => None,
+        });
+        let a = graph.get_vertex('a').unwrap();
+        let e = graph.get_vertex('e').unwrap();
+
+        assert_eq!(
+            graph.bellman_ford(a),
+            Ok(vec![Some(0), Some(1), Some(2), Some(4), Some(2)])
+        );
+        assert_eq!(
+            graph.bellman_ford(e),
+            Ok(vec![None, None, None, None, Some(0)])
+        );
+    }
+
+    #[test]
+    fn negative_cycle() {
+        let mut graph: Weighted<_, i32> = ('a'..='c').collect();
+        graph.construct_edges_from(|&from, &to| match (from, to) {
+            ('a', 'b') => Some(1),
+            ('b', 'c') => Some(-3),
+            ('c', 'a') => Some(1),
+            _ => None,
+        });
+        let a = graph.get_vertex('a').unwrap();
+
+        assert_eq!(graph.bellman_ford(a), Err(NegativeCycle));
+
+        let cycle = graph.find_negative_cycle(a).unwrap();
+        assert_eq!(cycle.len(), 3);
+        let weight: i32 = cycle
+            .iter()
+            .zip(cycle.iter().cycle().skip(1))
+            .map(|(&from, &to)| graph.get_edge(from, to).unwrap())
+            .sum();
+        assert!(weight < 0);
+
+        graph.remove_edge(a, graph.get_vertex('b').unwrap());
+        assert_eq!(graph.find_negative_cycle(a), None);
+    }
+
+    #[test]
+    fn shortest_path_dag() {
+        let mut graph: Weighted<_, i32> = ('a'..='f').collect();
+        graph.construct_edges_from(|&from, &to| match (from, to) {
+            ('a', 'b') => Some(5),
+            ('a', 'c') => Some(3),
+            ('b', 'd') => Some(6),
+            ('b', 'c') => Some(-2),
+            ('c', 'e') => Some(4),
+            ('c', 'd') => Some(7),
+            ('e', 'd') => Some(-1),
+            ('d', 'f') => Some(1),
+            _ => None,
+        });
+
+        for start in 'a'..='f' {
+            let start = graph.get_vertex(start).unwrap();
+            assert_eq!(
+                graph.shortest_path_dag(start).unwrap(),
+                graph.bellman_ford(start).unwrap()
+            );
+        }
+
+        let a = graph.get_vertex('a').unwrap();
+        graph.add_edge(graph.get_vertex('f').unwrap(), a, 1);
+        assert_eq!(graph.shortest_path_dag(a), Err(CycleError));
+    }
+
+    #[test]
+    fn dijkstra_all() {
+        let mut graph: Weighted<_, f32> = ('a'..='f').collect();
+        graph.construct_edges_from(|&from, &to| match (from, to) {
+            ('a', 'b') => Some(9.0),
+            ('a', 'd') => Some(8.0),
+            ('b', 'c') => Some(1.0),
+            ('b', 'e') => Some(3.0),
+            ('c', 'e') => Some(1.0),
+            ('d', 'c') => Some(5.0),
+            ('d', 'f') => Some(8.0),
+            ('e', 'f') => Some(6.0),
+            _ => None,
+        });
+        let handle = |v| graph.get_vertex(v).unwrap();
+        let path = |vertices: &[char]| vertices.iter().map(|&v| handle(v)).collect::<Vec<_>>();
+
+        let (dist, pred) = graph.dijkstra_all(handle('a'));
+        assert_eq!(
+            dist,
+            [
+                Some(0.0),
+                Some(9.0),
+                Some(10.0),
+                Some(8.0),
+                Some(11.0),
+                Some(16.0)
+            ]
+        );
+        assert_eq!(
+            path_from_predecessors(&pred, handle('a'), handle('e')),
+            Some(path(&['a', 'b', 'c', 'e']))
+        );
+        assert_eq!(
+            path_from_predecessors(&pred, handle('a'), handle('c')),
+            Some(path(&['a', 'b', 'c']))
+        );
+
+        assert_eq!(
+            graph.shortest_path_dijkstra(handle('a'), handle('f')),
+            Some((path(&['a', 'd', 'f']), 16.0))
+        );
+        assert_eq!(graph.shortest_path_dijkstra(handle('f'), handle('a')), None);
+    }
+
+    #[test]
+    fn minimum_mean_cycle() {
+        let mut graph: Weighted<_, f64> = ('a'..='e').collect();
+        graph.construct_edges_from(|&from, &to| match (from, to) {
+            ('a', 'b') => Some(4.0),
+            ('b', 'a') => Some(6.0),
+            ('b', 'c') => Some(0.0),
+            ('c', 'd') => Some(1.0),
+            ('d', 'e') => Some(2.0),
+            ('e', 'c') => Some(3.0),
+            _ => None,
+        });
+
+        let (mut cycle, mean) = graph.minimum_mean_cycle().unwrap();
+        assert!((mean - 2.0).abs() < 1e-9);
+        cycle.sort();
+        assert_eq!(cycle, ['c', 'd', 'e'].map(|v| graph.get_vertex(v).unwrap()));
+
+        graph.remove_edge(
+            graph.get_vertex('e').unwrap(),
+            graph.get_vertex('c').unwrap(),
+        );
+        let (mut cycle, mean) = graph.minimum_mean_cycle().unwrap();
+        assert!((mean - 5.0).abs() < 1e-9);
+        cycle.sort();
+        assert_eq!(cycle, ['a', 'b'].map(|v| graph.get_vertex(v).unwrap()));
+
+        graph.remove_edge(
+            graph.get_vertex('b').unwrap(),
+            graph.get_vertex('a').unwrap(),
+        );
+        assert_eq!(graph.minimum_mean_cycle(), None);
+    }
+
+    #[test]
+    fn steiner_tree_approx() {
+        // terminals a, b and c are connected directly and via the steiner vertex s
+        let mut graph: Weighted<_, f64> = ['a', 'b', 'c', 's', 'x'].into_iter().collect();
+        graph.construct_edges_from(|&from, &to| match (from.min(to), from.max(to)) {
+            (_, 's') if from != to && from != 'x' && to != 'x' => Some(1.0),
+            ('a', 'b') | ('b', 'c') | ('a', 'c') => Some(1.9),
+            ('a', 'x') => Some(0.5),
+            _ => None,
+        });
+        let terminals = ['a', 'b', 'c'].map(|v| graph.get_vertex(v).unwrap());
+
+        let tree = graph.steiner_tree_approx(&terminals);
+        assert_eq!(tree.size(), graph.size());
+
+        for &terminal in &terminals {
+            let reachable = tree.reachable_from(terminal);
+            assert!(terminals.iter().all(|&other| reachable[other.0]));
+        }
+
+        let weight: f64 = (0..tree.size())
+            .flat_map(|from| {
+                tree.neighbors(Handle(from))
+                    .iter()
+                    .filter(move |c| c.pointing_to().0 > from)
+                    .map(|c| c.weight())
+            })
+            .sum();
+        let optimum = 3.0;
+        assert!(weight <= 2.0 * optimum);
+
+        // the unnecessary vertex x is not part of the tree
+        let x = graph.get_vertex('x').unwrap();
+        assert_eq!(tree.connected_neighbors(x).count(), 0);
+    }
+
+    #[test]
+    fn within_cost() {
+        let mut path: Weighted<_, u32> = (0..6).collect();
+        path.construct_edges_from(|&from, &to| (to == from + 1).then_some(from + 1));
+        // distances from 0 are 0, 1, 3, 6, 10, 15
+
+        assert_eq!(path.within_cost(Handle(0), 0), [Handle(0)]);
+        assert_eq!(
+            path.within_cost(Handle(0), 6),
+            [Handle(0), Handle(1), Handle(2), Handle(3)]
+        );
+        assert_eq!(
+            path.within_cost(Handle(0), 9),
+            [Handle(0), Handle(1), Handle(2), Handle(3)]
+        );
+        assert_eq!(path.within_cost(Handle(3), 8), [Handle(3), Handle(4)]);
+        assert_eq!(path.within_cost(Handle(5), 100), [Handle(5)]);
+    }
+
+    #[test]
+    fn chinese_postman() {
+        fn assert_valid_walk(graph: &Weighted<char, u32>, walk: &[Handle], edges: usize) {
+            assert_eq!(walk.len(), edges + 1);
+            assert_eq!(walk.first(), walk.last());
+            for pair in walk.windows(2) {
+                assert!(graph.has_edge(pair[0], pair[1]));
+            }
+        }
+
+        let mut graph: Weighted<_, u32> = ('a'..='d').collect();
+        graph.construct_edges_from(|&u, &v| match (u.min(v), u.max(v)) {
+            ('a', 'b') | ('c', 'd') => Some(3),
+            ('b', 'c') | ('a', 'd') => Some(1),
+            ('a', 'c') => Some(5),
+            _ => None,
+        });
+        let (walk, cost) = graph.chinese_postman().unwrap();
+        // the odd vertices a and c are joined by a path of weight 4
+        assert_eq!(cost, 17);
+        assert_valid_walk(&graph, &walk, 7);
+
+        let mut complete: Weighted<_, u32> = ('a'..='d').collect();
+        complete.construct_edges_from(|&u, &v| (u != v).then_some(1));
+        let (walk, cost) = complete.chinese_postman().unwrap();
+        assert_eq!(cost, 8);
+        assert_valid_walk(&complete, &walk, 8);
+
+        // too many odd vertices for the exact matching, but in a star every matching doubles all edges
+        let mut star: Weighted<_, u32> = ('a'..='u').collect();
+        star.construct_edges_from(|&u, &v| (u != v && u.min(v) == 'a').then_some(2));
+        let (walk, cost) = star.chinese_postman().unwrap();
+        assert_eq!(cost, 80);
+        assert_valid_walk(&star, &walk, 40);
+
+        let mut disconnected: Weighted<_, u32> = ('a'..='d').collect();
+        disconnected.construct_edges_from(|&u, &v| (u.max(v) == 'b' && u != v).then_some(1));
+        assert_eq!(disconnected.chinese_postman(), None);
+    }
+
+    #[test]
+    fn min_cost_matching() {
+        let costs = [[4, 1, 3, 6], [2, 0, 5, 3], [3, 2, 2, 4], [7, 6, 8, 1]];
+        let mut graph: Weighted<_, u32> = (0..8).collect();
+        graph.construct_edges_from(|&u, &v| (u < 4 && v >= 4).then(|| costs[u][v - 4]));
+        let workers: Vec<_> = (0..4).map(Handle).collect();
+        let jobs: Vec<_> = (4..8).map(Handle).collect();
+
+        let (pairs, cost) = graph.min_cost_matching(&workers, &jobs).unwrap();
+        assert_eq!(cost, 6);
+        assert_eq!(
+            pairs,
+            [
+                (workers[0], jobs[1]),
+                (workers[1], jobs[0]),
+                (workers[2], jobs[2]),
+                (workers[3], jobs[3])
+            ]
+        );
+
+        // without the cheap edges of the first worker, it has to take the last job
+        graph.retain_edges(|from, to, _| !(from == workers[0] && to != jobs[3]));
+        let (pairs, cost) = graph.min_cost_matching(&workers, &jobs).unwrap();
+        assert_eq!(pairs[0], (workers[0], jobs[3]));
+        assert_eq!(cost, 6 + 9);
+
+        graph.retain_edges(|from, _, _| from != workers[3]);
+        assert_eq!(graph.min_cost_matching(&workers, &jobs), None);
+        assert_eq!(graph.min_cost_matching(&workers[..2], &jobs), None);
+        assert_eq!(graph.min_cost_matching(&[], &[]), Some((vec![], 0)));
+    }
+
+    #[test]
+    fn weighted_diameter() {
+        let weights = [3, 1, 4, 1, 5];
+        let mut path: Weighted<_, u32> = (0..6).collect();
+        path.construct_undirected_edges_from(|&u: &usize, &v| (v == u + 1).then(|| weights[u]));
+
+        assert_eq!(path.weighted_diameter(), Some(weights.iter().sum()));
+        assert_eq!(path.weighted_eccentricity(Handle(0)), Some(14));
+        assert_eq!(path.weighted_eccentricity(Handle(2)), Some(10));
+        assert_eq!(path.weighted_eccentricity(Handle(3)), Some(8));
+
+        path.add_vertex(6);
+        assert_eq!(path.weighted_eccentricity(Handle(0)), None);
+        assert_eq!(path.weighted_diameter(), None);
+        assert_eq!(Weighted::<u8, u32>::new().weighted_diameter(), None);
+
+        let single: Weighted<_, u32> = (0..1).collect();
+        assert_eq!(single.weighted_diameter(), Some(0));
+    }
+
+    #[test]
+    fn most_reliable_path() {
+        let mut graph: Weighted<_, f64> = ('a'..='d').collect();
+        graph.construct_edges_from(|&from, &to| match (from, to) {
+            ('a', 'b') | ('b', 'c') => Some(0.9),
+            ('a', 'c') => Some(0.5),
+            ('c', 'd') => Some(0.0),
+            _ => None,
+        });
+        let (a, b, c, d) = (Handle(0), Handle(1), Handle(2), Handle(3));
+
+        assert_eq!(graph.shortest_path_dijkstra(a, c).unwrap().0, [a, c]);
+        let (path, reliability) = graph.most_reliable_path(a, c).unwrap();
+        assert_eq!(path, [a, b, c]);
+        assert!((reliability - 0.81).abs() < 1e-9);
+
+        assert_eq!(
+            graph.most_reliable_path(a, d).map(|(path, _)| path.len()),
+            Some(4)
+        );
+        assert_eq!(graph.most_reliable_path(b, a), None);
+        assert_eq!(graph.most_reliable_path(a, a), Some((vec![a], 1.0)));
+    }
+
+    #[test]
+    fn shortest_path_spfa() {
+        // forward edges may be negative, but every backward edge outweighs any forward path
+        let mut graph: Weighted<_, i32> = (0..9).collect();
+        graph.construct_edges_from(|&u, &v| match (u, v) {
+            (8, _) | (_, 8) => None,
+            _ if u < v && (u * 3 + v) % 4 != 0 => Some((u * 5 + v * 3) % 11 - 3),
+            _ if u > v && (u + v) % 3 == 0 => Some(30),
+            _ => None,
+        });
+        for start in (0..9).map(Handle) {
+            assert_eq!(graph.shortest_path_spfa(start), graph.bellman_ford(start));
+        }
+        let dist = graph.shortest_path_spfa(Handle(0)).unwrap();
+        assert!(dist.iter().flatten().any(|&dist| dist < 0));
+        assert_eq!(dist[8], None);
+
+        graph.add_edge(Handle(7), Handle(6), -20);
+        graph.add_edge(Handle(6), Handle(7), 1);
+        assert_eq!(graph.shortest_path_spfa(Handle(0)), Err(NegativeCycle));
+        assert_eq!(graph.bellman_ford(Handle(0)), Err(NegativeCycle));
+    }
+
+    #[test]
+    fn minimum_bottleneck_path() {
+        let mut graph: Weighted<_, u32> = ('a'..='e').collect();
+        graph.construct_edges_from(|&from, &to| match (from, to) {
+            ('a', 'e') => Some(10),
+            ('a', 'b') => Some(4),
+            ('b', 'c') => Some(5),
+            ('c', 'd') => Some(3),
+            ('d', 'e') => Some(4),
+            _ => None,
+        });
+        let (a, e) = (Handle(0), Handle(4));
+
+        let (path, total) = graph.shortest_path_dijkstra(a, e).unwrap();
+        assert_eq!((path, total), (vec![a, e], 10));
+
+        let (path, bottleneck) = graph.minimum_bottleneck_path(a, e).unwrap();
+        assert_eq!(bottleneck, 5);
+        assert_eq!(path, [a, Handle(1), Handle(2), Handle(3), e]);
+
+        assert_eq!(graph.minimum_bottleneck_path(e, a), None);
+        assert_eq!(graph.minimum_bottleneck_path(a, a), Some((vec![a], 0)));
+    }
+
+    #[test]
+    fn minimum_spanning_tree() {
+        let mut graph: Weighted<_, u32> = (0..7).collect();
+        graph.construct_edges_from(|&u, &v| match (u, v) {
+            (0, 1) => Some(7),
+            (0, 3) => Some(5),
+            (1, 2) => Some(8),
+            (1, 3) => Some(9),
+            (1, 4) => Some(7),
+            (2, 4) => Some(5),
+            (3, 4) => Some(15),
+            (3, 5) => Some(6),
+            (4, 5) => Some(8),
+            (4, 6) => Some(9),
+            (5, 6) => Some(11),
+            _ => None,
+        });
+
+        let tree = graph.minimum_spanning_tree();
+        assert_eq!(tree.num_edges(), 12);
+        let tree_weight: u32 = tree.edges_with_values().map(|(_, _, weight)| weight).sum();
+        assert_eq!(tree_weight / 2, 39);
+        assert_eq!(graph.mst_weight(), tree_weight / 2);
+
+        let mut forest: Weighted<_, u32> = (0..4).collect();
+        forest.construct_edges_from(|&u, &v| (u + 1 == v && v != 2).then_some(u + v));
+        assert_eq!(forest.mst_weight(), 1 + 5);
+        assert_eq!(forest.minimum_spanning_tree().num_edges(), 4);
+    }
+
+    #[test]
+    fn all_pairs_dijkstra() {
+        let mut graph: Weighted<_, u64> = (0..30).collect();
+        graph.construct_edges_from(|&u, &v| {
+            let weight = (u * 7 + v * 13) % 11;
+            (u != v && weight < 3).then_some(weight + u % 4)
+        });
+
+        let distances = graph.all_pairs_dijkstra();
+        assert_eq!(distances.len(), 30);
+        for (start, row) in distances.iter().enumerate() {
+            let expected = graph.bellman_ford(Handle(start)).unwrap();
+            assert_eq!(*row, expected);
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_all_pairs_dijkstra() {
+        let mut graph: Weighted<_, u64> = (0..60).collect();
+        graph.construct_edges_from(|&u, &v| {
+            let weight = (u * 7 + v * 13) % 17;
+            (u != v && weight < 3).then_some(weight + u % 5)
+        });
+
+        assert_eq!(graph.par_all_pairs_dijkstra(), graph.all_pairs_dijkstra());
+    }
+
+    #[test]
+    fn shortest_path() {
+        let mut graph: Weighted<_, i32> = ('a'..='e').collect();
+        graph.construct_edges_from(|&from, &to| match (from, to) {
+            ('a', 'b') => Some(4),
+            ('a', 'c') => Some(2),
+            ('c', 'b') => Some(1),
+            ('b', 'd') => Some(3),
+            ('d', 'e') => Some(2),
+            ('c', 'e') => Some(9),
+            _ => None,
+        });
+        let (a, e) = (Handle(0), Handle(4));
+
+        assert!(!graph.has_negative_weights());
+        let (path, cost) = graph.shortest_path(a, e).unwrap();
+        assert_eq!(
+            Some((path.clone(), cost)),
+            graph.shortest_path_dijkstra(a, e)
+        );
+        assert_eq!(path, [a, Handle(2), Handle(1), Handle(3), e]);
+        assert_eq!(cost, 8);
+
+        graph.remove_edge(Handle(2), Handle(1));
+        graph.add_edge(Handle(2), Handle(1), -1);
+        assert!(graph.has_negative_weights());
+        let (path, cost) = graph.shortest_path(a, e).unwrap();
+        assert_eq!(path, [a, Handle(2), Handle(1), Handle(3), e]);
+        assert_eq!(Ok(Some(cost)), graph.bellman_ford(a).map(|dist| dist[e.0]));
+        assert_eq!(cost, 6);
+        assert_eq!(graph.shortest_path(e, a), None);
+
+        graph.add_edge(Handle(1), Handle(2), -1);
+        assert_eq!(graph.shortest_path(a, e), None);
+    }
+
+    #[test]
+    fn max_flow() {
+        let mut graph: Weighted<_, u32> = ["s1", "s2", "a", "b", "t"].into_iter().collect();
+        graph.construct_edges_from(|&from, &to| match (from, to) {
+            ("s1", "a") => Some(3),
+            ("s2", "a") => Some(2),
+            ("s2", "b") => Some(4),
+            ("a", "t") => Some(4),
+            ("a", "b") => Some(1),
+            ("b", "t") => Some(1),
+            _ => None,
+        });
+        let (s1, s2, t) = (Handle(0), Handle(1), Handle(4));
+
+        assert_eq!(graph.max_flow(s1, t), 3);
+        assert_eq!(graph.max_flow(s2, t), 3);
+        assert_eq!(graph.max_flow(t, s1), 0);
+
+        let (network, source, sink) = graph.to_flow_network(&[s1, s2], &[t]);
+        assert_eq!(network.size(), 7);
+        assert_eq!(network.max_flow(source, sink), 5);
+    }
+
+    #[test]
+    fn shortest_path_with_node_cost() {
+        //   b
+        //  / \
+        // a   d
+        //  \ /
+        //   c
+        let mut graph: Weighted<_, u32> = ('a'..='d').collect();
+        graph.construct_edges_from(|&from, &to| match (from, to) {
+            ('a', 'b') | ('b', 'd') => Some(2),
+            ('a', 'c') | ('c', 'd') => Some(3),
+            _ => None,
+        });
+        let (a, b, c, d) = (Handle(0), Handle(1), Handle(2), Handle(3));
+
+        let free = graph.shortest_path_with_node_cost(a, d, |_| 0);
+        assert_eq!(free, Some((vec![a, b, d], 4)));
+        assert_eq!(free, graph.shortest_path_dijkstra(a, d));
+
+        // waiting at b makes the route via c cheaper, while the endpoints are never charged
+        let cost = |vertex| match vertex {
+            Handle(1) => 5,
+            Handle(2) => 1,
+            _ => 100,
+        };
+        assert_eq!(
+            graph.shortest_path_with_node_cost(a, d, cost),
+            Some((vec![a, c, d], 7))
+        );
+        assert_eq!(
+            graph.shortest_path_with_node_cost(a, b, cost),
+            Some((vec![a, b], 2))
+        );
+        assert_eq!(graph.shortest_path_with_node_cost(d, a, cost), None);
+    }
+
+    #[test]
+    fn second_shortest_path() {
+        //   b
+        //  / \
+        // a - d - e
+        //  \ /
+        //   c
+        let mut graph: Weighted<_, u32> = ('a'..='e').collect();
+        graph.construct_edges_from(|&from, &to| match (from, to) {
+            ('a', 'b') | ('b', 'd') => Some(2),
+            ('a', 'c') | ('c', 'd') => Some(3),
+            ('a', 'd') => Some(4),
+            ('d', 'e') => Some(1),
+            _ => None,
+        });
+        let (a, c, d, e) = (Handle(0), Handle(2), Handle(3), Handle(4));
+
+        // a-b-d and a-d both cost 4, so the second shortest path is the one via c
+        assert_eq!(graph.second_shortest_path(a, d), Some((vec![a, c, d], 6)));
+        assert_eq!(
+            graph.second_shortest_path(a, e),
+            Some((vec![a, c, d, e], 7))
+        );
+        assert_eq!(graph.second_shortest_path(d, e), None);
+        assert_eq!(graph.second_shortest_path(e, a), None);
+
+        let mut line: Weighted<_, u32> = (0..2).collect();
+        line.construct_undirected_edges_from(|u, v| (u != v).then_some(2));
+        assert_eq!(
+            line.second_shortest_path(Handle(0), Handle(1)),
+            Some((vec![Handle(0), Handle(1), Handle(0), Handle(1)], 6))
+        );
+    }
+
+    #[test]
+    fn components_above_threshold() {
+        // two tightly connected triangles joined by a weak edge
+        let mut graph: Weighted<_, f64> = (0..6).collect();
+        graph.construct_undirected_edges_from(|&u, &v| {
+            if u == v {
+                None
+            } else if u / 3 == v / 3 {
+                Some(0.9)
+            } else {
+                (u == 2 && v == 3).then_some(0.4)
+            }
+        });
+
+        assert_eq!(
+            graph.components_above_threshold(0.3),
+            [(0..6).map(Handle).collect::<Vec<_>>()]
+        );
+        assert_eq!(
+            graph.components_above_threshold(0.5),
+            [
+                vec![Handle(0), Handle(1), Handle(2)],
+                vec![Handle(3), Handle(4), Handle(5)]
+            ]
+        );
+        assert_eq!(graph.components_above_threshold(1.0).len(), 6);
+    }
+}
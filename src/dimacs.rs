@@ -0,0 +1,227 @@
+//! Reading and writing weighted graphs in the DIMACS shortest path format
+//!
+//! A graph in this format consists of a problem line `p sp n m` announcing `n` vertices and `m` arcs,
+//! followed by one line `a u v w` for every arc from `u` to `v` with weight `w`. Vertices are numbered
+//! from `1` to `n` and lines starting with `c` are comments.
+//!
+//! ```
+//! # use comtesse::weighted::Weighted;
+//! let graph = Weighted::from_dimacs("p sp 2 1\na 1 2 7\n").unwrap();
+//! assert_eq!(graph.to_dimacs(), "p sp 2 1\na 1 2 7\n");
+//! ```
+
+use std::fmt::{Display, Write};
+
+use crate::{
+    graph::{Graph, Handle},
+    weighted::Weighted,
+};
+
+/// Problem lines may always announce up to this many vertices, see [Weighted::from_dimacs]
+pub const SMALL_GRAPH_VERTICES: usize = 1 << 20;
+
+/// Error returned by [Weighted::from_dimacs] if the input is not a valid graph in the DIMACS format.
+/// Lines are numbered from `1`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DimacsError {
+    /// The input does not contain a problem line before the first arc
+    MissingProblemLine,
+    /// The line could not be parsed
+    InvalidLine(usize),
+    /// The arc in this line refers to a vertex that does not exist
+    VertexOutOfRange(usize),
+    /// The problem line in this line announces more vertices than [Weighted::from_dimacs] accepts
+    TooLarge(usize),
+    /// The number of arcs does not match the problem line
+    WrongArcCount {
+        /// the number of arcs announced in the problem line
+        expected: usize,
+        /// the number of arcs in the input
+        found: usize,
+    },
+}
+
+impl Display for DimacsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DimacsError::MissingProblemLine => write!(f, "missing problem line"),
+            DimacsError::InvalidLine(line) => write!(f, "invalid line {line}"),
+            DimacsError::VertexOutOfRange(line) => {
+                write!(
+                    f,
+                    "arc in line {line} refers to a vertex that does not exist"
+                )
+            }
+            DimacsError::TooLarge(line) => {
+                write!(f, "problem line {line} announces too many vertices")
+            }
+            DimacsError::WrongArcCount { expected, found } => {
+                write!(f, "expected {expected} arcs but found {found}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DimacsError {}
+
+impl<V, W> Weighted<V, W>
+where
+    W: num_traits::Num + Copy + Display,
+{
+    /// Returns the graph in the DIMACS shortest path format, where the vertex with handle `h`
+    /// is numbered `h.index() + 1`. The values of the vertices are not part of the output
+    pub fn to_dimacs(&self) -> String {
+        let mut output = format!("p sp {} {}\n", self.size(), self.num_edges());
+        for (from, connections) in self.edges.iter().enumerate() {
+            for connection in connections {
+                writeln!(
+                    output,
+                    "a {} {} {}",
+                    from + 1,
+                    connection.pointing_to().0 + 1,
+                    connection.weight()
+                )
+                .expect("writing to a String does not fail");
+            }
+        }
+        output
+    }
+}
+
+impl Weighted<usize, i64> {
+    /// Parses a graph in the DIMACS shortest path format. Every vertex holds its number from the input,
+    /// so the vertex numbered `k` has the handle `k - 1`.
+    ///
+    /// Comment lines, empty lines and the node descriptor lines of the flow format (`n ...`) are skipped.
+    /// Returns a [DimacsError] if the input is malformed.
+    ///
+    /// Since the vertices are allocated up front, the problem line may announce at most [SMALL_GRAPH_VERTICES] vertices
+    /// or as many vertices as the input has bytes, whichever is larger. Otherwise [DimacsError::TooLarge] is returned,
+    /// so that a short input can not make the parser allocate huge amounts of memory.
+    pub fn from_dimacs(input: &str) -> Result<Weighted<usize, i64>, DimacsError> {
+        let mut graph = None;
+        let mut expected_arcs = 0;
+
+        for (line_number, line) in (1..).zip(input.lines()) {
+            let mut fields = line.split_whitespace();
+            let invalid = DimacsError::InvalidLine(line_number);
+            match fields.next() {
+                None | Some("c") | Some("n") => continue,
+                Some("p") => {
+                    if graph.is_some() {
+                        return Err(invalid);
+                    }
+                    let _problem = fields.next().ok_or(invalid)?;
+                    let vertices: usize = parse_field(fields.next(), invalid)?;
+                    if vertices > input.len().max(SMALL_GRAPH_VERTICES) {
+                        return Err(DimacsError::TooLarge(line_number));
+                    }
+                    expected_arcs = parse_field(fields.next(), invalid)?;
+                    let graph = graph.insert(Graph::new());
+                    graph
+                        .vertices
+                        .try_reserve_exact(vertices)
+                        .and_then(|()| graph.edges.try_reserve_exact(vertices))
+                        .map_err(|_| DimacsError::TooLarge(line_number))?;
+                    graph.vertices.extend(1..=vertices);
+                    graph.edges.resize_with(vertices, Vec::new);
+                }
+                Some("a") => {
+                    let graph: &mut Weighted<usize, i64> =
+                        graph.as_mut().ok_or(DimacsError::MissingProblemLine)?;
+                    let from: usize = parse_field(fields.next(), invalid)?;
+                    let to: usize = parse_field(fields.next(), invalid)?;
+                    let weight = parse_field(fields.next(), invalid)?;
+
+                    let in_range = |vertex: usize| (1..=graph.size()).contains(&vertex);
+                    if !in_range(from) || !in_range(to) {
+                        return Err(DimacsError::VertexOutOfRange(line_number));
+                    }
+                    graph.add_edge(Handle(from - 1), Handle(to - 1), weight);
+                }
+                Some(_) => return Err(invalid),
+            }
+            if fields.next().is_some() {
+                return Err(invalid);
+            }
+        }
+
+        let graph = graph.ok_or(DimacsError::MissingProblemLine)?;
+        if graph.num_edges() != expected_arcs {
+            return Err(DimacsError::WrongArcCount {
+                expected: expected_arcs,
+                found: graph.num_edges(),
+            });
+        }
+        Ok(graph)
+    }
+}
+
+/// Parses a whitespace separated field, returning `error` if it is missing or invalid
+fn parse_field<T: std::str::FromStr>(
+    field: Option<&str>,
+    error: DimacsError,
+) -> Result<T, DimacsError> {
+    field.and_then(|field| field.parse().ok()).ok_or(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DimacsError, SMALL_GRAPH_VERTICES};
+    use crate::{graph::Handle, weighted::Weighted};
+
+    #[test]
+    fn round_trip() {
+        let mut graph: Weighted<_, i64> = (0..5).collect();
+        graph.construct_edges_from(|&u, &v| (u != v && (u + v) % 3 == 0).then_some(u - 2 * v));
+
+        let dimacs = graph.to_dimacs();
+        assert!(dimacs.starts_with("p sp 5 6\n"));
+        let parsed = Weighted::from_dimacs(&dimacs).unwrap();
+        assert_eq!(parsed.size(), graph.size());
+        assert_eq!(parsed.to_dimacs(), dimacs);
+        for from in (0..5).map(Handle) {
+            for to in (0..5).map(Handle) {
+                assert_eq!(parsed.get_edge(from, to), graph.get_edge(from, to));
+            }
+        }
+        assert_eq!(*parsed.vertex_value(Handle(0)), 1);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let comments = "c a small graph\n\np sp 3 2\nc arcs follow\na 1 2 4\na 3 1 -2\n";
+        let graph = Weighted::from_dimacs(comments).unwrap();
+        assert_eq!(graph.get_edge(Handle(2), Handle(0)), Some(-2));
+
+        assert_eq!(
+            Weighted::from_dimacs("a 1 2 3\n").err(),
+            Some(DimacsError::MissingProblemLine)
+        );
+        assert_eq!(
+            Weighted::from_dimacs("p sp 2 1\na 1 x 3\n").err(),
+            Some(DimacsError::InvalidLine(2))
+        );
+        assert_eq!(
+            Weighted::from_dimacs("p sp 2 1\na 1 3 3\n").err(),
+            Some(DimacsError::VertexOutOfRange(2))
+        );
+        assert_eq!(
+            Weighted::from_dimacs("p sp 18446744073709551615 0\n").err(),
+            Some(DimacsError::TooLarge(1))
+        );
+        assert_eq!(
+            Weighted::from_dimacs("c tiny input\np sp 1000000000 0\n").err(),
+            Some(DimacsError::TooLarge(2))
+        );
+        let isolated = Weighted::from_dimacs(&format!("p sp {SMALL_GRAPH_VERTICES} 0\n")).unwrap();
+        assert_eq!(isolated.size(), SMALL_GRAPH_VERTICES);
+        assert_eq!(
+            Weighted::from_dimacs("p sp 2 2\na 1 2 3\n").err(),
+            Some(DimacsError::WrongArcCount {
+                expected: 2,
+                found: 1
+            })
+        );
+    }
+}
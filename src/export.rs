@@ -0,0 +1,149 @@
+//! writers for the DOT and GraphML formats shared by the different kinds of graphs
+
+use std::fmt::{Debug, Display, Write};
+
+use crate::{
+    graph::{Edge, Graph},
+    make_safer, make_xml_safe,
+};
+
+impl<V, E> Graph<V, E>
+where
+    V: Debug,
+    E: Edge,
+{
+    /// Writes the graph in the DOT format. `attributes` is given the index of the vertex an edge starts at
+    /// and the edge itself and returns the attribute list of the edge, which is left out if it is empty
+    pub(crate) fn write_dot(
+        &self,
+        output: &mut dyn Write,
+        attributes: impl Fn(usize, &E) -> String,
+    ) -> std::fmt::Result {
+        let labels = self.dot_labels();
+
+        writeln!(output, "digraph {{")?;
+        for vertex in &labels {
+            writeln!(output, "  \"{vertex}\";")?;
+        }
+        for (from, edges) in self.edges.iter().enumerate() {
+            for edge in edges {
+                let to = &labels[edge.pointing_to().0];
+                write_dot_edge(output, &labels[from], to, &attributes(from, edge))?;
+            }
+        }
+        writeln!(output, "}}")
+    }
+
+    /// Returns the graph in the DOT format like [Graph::write_dot], but with the vertices and edges
+    /// sorted by their labels and edges between the same vertices sorted by their attribute lists
+    pub(crate) fn to_dot_sorted_with(&self, attributes: impl Fn(usize, &E) -> String) -> String {
+        let labels = self.dot_labels();
+
+        let mut vertices: Vec<_> = labels.iter().collect();
+        vertices.sort_unstable();
+        let mut edges: Vec<_> = self
+            .edges
+            .iter()
+            .enumerate()
+            .flat_map(|(from, edges)| edges.iter().map(move |edge| (from, edge)))
+            .map(|(from, edge)| {
+                let to = &labels[edge.pointing_to().0];
+                (&labels[from], to, attributes(from, edge))
+            })
+            .collect();
+        edges.sort_unstable();
+
+        let mut output = String::from("digraph {\n");
+        for vertex in vertices {
+            writeln!(output, "  \"{vertex}\";").expect("writing to a String does not fail");
+        }
+        for (from, to, attributes) in edges {
+            write_dot_edge(&mut output, from, to, &attributes)
+                .expect("writing to a String does not fail");
+        }
+        output += "}\n";
+        output
+    }
+
+    fn dot_labels(&self) -> Vec<String> {
+        // TODO: vertex:? could inject stuff
+        self.vertices
+            .iter()
+            .map(|vertex| make_safer(&format!("{vertex:?}")).into_owned())
+            .collect()
+    }
+}
+
+fn write_dot_edge(
+    output: &mut dyn Write,
+    from: &str,
+    to: &str,
+    attributes: &str,
+) -> std::fmt::Result {
+    if attributes.is_empty() {
+        writeln!(output, "  \"{from}\" -> \"{to}\";")
+    } else {
+        writeln!(output, "  \"{from}\" -> \"{to}\" [{attributes}];")
+    }
+}
+
+/// The GraphML type of the edge weights and a function returning the weight of an edge
+type GraphmlWeight<'a, E> = (&'a str, &'a dyn Fn(&E) -> String);
+
+impl<V, E> Graph<V, E>
+where
+    V: Display,
+    E: Edge,
+{
+    /// Writes the graph in the GraphML format, using the values of the vertices as their labels.
+    /// If `weight` is given, it declares the GraphML type of the weights and returns the weight of an edge,
+    /// which is then stored as edge data
+    pub(crate) fn write_graphml(
+        &self,
+        output: &mut dyn Write,
+        weight: Option<GraphmlWeight<E>>,
+    ) -> std::fmt::Result {
+        writeln!(output, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            output,
+            r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+        )?;
+        writeln!(
+            output,
+            r#"  <key id="label" for="node" attr.name="label" attr.type="string"/>"#
+        )?;
+        if let Some((weight_type, _)) = weight {
+            writeln!(
+                output,
+                r#"  <key id="weight" for="edge" attr.name="weight" attr.type="{weight_type}"/>"#
+            )?;
+        }
+        writeln!(output, r#"  <graph id="G" edgedefault="directed">"#)?;
+        for (i, vertex) in self.vertices.iter().enumerate() {
+            let vertex = vertex.to_string();
+            let vertex = make_xml_safe(&vertex);
+            writeln!(
+                output,
+                r#"    <node id="n{i}"><data key="label">{vertex}</data></node>"#
+            )?;
+        }
+        for (from, edges) in self.edges.iter().enumerate() {
+            for edge in edges {
+                let to = edge.pointing_to().0;
+                match weight {
+                    Some((_, weight)) => {
+                        let weight = weight(edge);
+                        let weight = make_xml_safe(&weight);
+                        writeln!(
+                            output,
+                            r#"    <edge source="n{from}" target="n{to}"><data key="weight">{weight}</data></edge>"#
+                        )?;
+                    }
+                    None => writeln!(output, r#"    <edge source="n{from}" target="n{to}"/>"#)?,
+                }
+            }
+        }
+        writeln!(output, "  </graph>")?;
+        writeln!(output, "</graphml>")
+    }
+}
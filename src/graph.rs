@@ -2,9 +2,12 @@
 
 use std::iter::repeat_with;
 
+use crate::SizeMismatch;
+
 /// A generic Graph, containing vertices of type `V`, connected by type `E`
 ///
 /// This is rarely used directly. Instead use [crate::unweighted::Unweighted] or [crate::weighted::Weighted]
+#[derive(Clone)]
 pub struct Graph<V, E> {
     pub(crate) vertices: Vec<V>,
     pub(crate) edges: Vec<Vec<E>>,
@@ -14,6 +17,128 @@ pub struct Graph<V, E> {
 #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
 pub struct Handle(pub(crate) usize);
 
+impl Handle {
+    /// Creates a handle to the vertex with index `index`, i.e. the `index`-th vertex added to a graph.
+    ///
+    /// This is unchecked: the handle is not tied to any graph and may not refer to an existing vertex.
+    /// Use [Graph::is_valid_handle] before using handles created from untrusted indices,
+    /// since graph methods panic when given an out-of-range handle.
+    pub fn from_index(index: usize) -> Handle {
+        Handle(index)
+    }
+
+    /// Returns the index of the vertex this handle refers to
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// Maps the handles of a graph before a structural change, like [Graph::remove_vertex],
+/// to the handles of the same vertices after the change
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct HandleRemap(pub(crate) Vec<Option<Handle>>);
+
+impl HandleRemap {
+    /// Returns the new handle of the vertex that had the handle `old`,
+    /// or `None` if the vertex has been removed or `old` was not a valid handle
+    pub fn map(&self, old: Handle) -> Option<Handle> {
+        self.0.get(old.0).copied().flatten()
+    }
+
+    /// Returns the number of handles that were valid before the change
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether the graph was empty before the change
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Stores a value of type `T` for every vertex of a graph, indexed by [Handle]
+///
+/// ```
+/// # use comtesse::{graph::HandleMap, unweighted::Unweighted};
+/// let graph: Unweighted<_> = ('a'..='c').collect();
+/// let b = graph.get_vertex('b').unwrap();
+///
+/// let mut visited = HandleMap::filled(&graph, false);
+/// *visited.get_mut(b) = true;
+/// assert!(*visited.get(b));
+/// ```
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct HandleMap<T>(Vec<T>);
+
+impl<T: Clone> HandleMap<T> {
+    /// Creates a map that stores `value` for every vertex of `graph`
+    pub fn filled<V, E>(graph: &Graph<V, E>, value: T) -> Self {
+        HandleMap(vec![value; graph.size()])
+    }
+}
+
+impl<T> HandleMap<T> {
+    /// Returns the value stored for `vertex`
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `vertex` is not a vertex of the graph the map was created for
+    pub fn get(&self, vertex: Handle) -> &T {
+        &self.0[vertex.0]
+    }
+
+    /// Returns a mutable reference to the value stored for `vertex`
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `vertex` is not a vertex of the graph the map was created for
+    pub fn get_mut(&mut self, vertex: Handle) -> &mut T {
+        &mut self.0[vertex.0]
+    }
+
+    /// Returns the number of vertices the map stores values for
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether the map was created for an empty graph
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns an iterator over every vertex and its value, ordered by handle
+    pub fn iter(&self) -> impl Iterator<Item = (Handle, &T)> {
+        self.0
+            .iter()
+            .enumerate()
+            .map(|(i, value)| (Handle(i), value))
+    }
+
+    /// Returns the values, indexed by handle
+    pub fn into_vec(self) -> Vec<T> {
+        self.0
+    }
+}
+
+/// An edge as stored in the adjacency list of a [Graph], pointing to some vertex
+pub trait Edge {
+    /// Returns a handle to the vertex being pointed to
+    fn pointing_to(&self) -> Handle;
+
+    /// Makes the edge point to `to` instead, keeping everything else about it
+    fn redirect(&mut self, to: Handle);
+}
+
+impl Edge for Handle {
+    fn pointing_to(&self) -> Handle {
+        *self
+    }
+
+    fn redirect(&mut self, to: Handle) {
+        *self = to;
+    }
+}
+
 impl<V, E> Graph<V, E> {
     /// Constructs a new, empty `Graph<V>`
     pub fn new() -> Self {
@@ -51,10 +176,279 @@ impl<V, E> Graph<V, E> {
         self.edges.iter().map(|elem| elem.len()).sum()
     }
 
+    /// Returns whether the graph contains at least one edge. Unlike comparing [Graph::num_edges] to zero,
+    /// this stops at the first vertex with an outgoing edge.
+    pub fn has_any_edge(&self) -> bool {
+        self.edges.iter().any(|elem| !elem.is_empty())
+    }
+
+    /// Removes all edges from the graph, keeping all vertices and their handles
+    pub fn clear_edges(&mut self) {
+        for edges in &mut self.edges {
+            edges.clear();
+        }
+    }
+
+    /// Returns whether `handle` refers to a vertex of this graph
+    pub fn is_valid_handle(&self, handle: Handle) -> bool {
+        handle.0 < self.vertices.len()
+    }
+
     /// Returns the value associated with `vertex`
     pub fn vertex_value(&self, vertex: Handle) -> &V {
         &self.vertices[vertex.0]
     }
+
+    /// Returns the number of vertices whose value satisfies `pred`
+    pub fn count_vertices(&self, pred: impl Fn(&V) -> bool) -> usize {
+        self.vertices.iter().filter(|vertex| pred(vertex)).count()
+    }
+
+    /// Returns the handles of all vertices whose value satisfies `pred`, sorted by handle
+    pub fn find_vertices(&self, pred: impl Fn(&V) -> bool) -> Vec<Handle> {
+        self.vertices
+            .iter()
+            .enumerate()
+            .filter(|(_, vertex)| pred(vertex))
+            .map(|(i, _)| Handle(i))
+            .collect()
+    }
+
+    /// Returns a copy of the graph where the vertex with handle `h` holds `labels[h.index()]` instead of its value.
+    /// All edges and handles stay the same.
+    ///
+    /// Returns [SizeMismatch] if there is not exactly one label for every vertex.
+    ///
+    /// ```
+    /// # use comtesse::unweighted::Unweighted;
+    /// let mut graph: Unweighted<_> = ('a'..='c').collect();
+    /// graph.construct_edges_from(|&u, &v| u < v);
+    ///
+    /// let communities = graph.relabel(&[0, 0, 1]).unwrap();
+    /// assert_eq!(communities.num_edges(), 3);
+    /// assert!(graph.relabel(&[0, 1]).is_err());
+    /// ```
+    pub fn relabel<U>(&self, labels: &[U]) -> Result<Graph<U, E>, SizeMismatch>
+    where
+        U: Clone,
+        E: Clone,
+    {
+        if labels.len() != self.size() {
+            return Err(SizeMismatch {
+                expected: self.size(),
+                found: labels.len(),
+            });
+        }
+
+        Ok(Graph {
+            vertices: labels.to_vec(),
+            edges: self.edges.clone(),
+        })
+    }
+
+    /// Reserves capacity for at least `additional` more vertices
+    pub fn reserve(&mut self, additional: usize) {
+        self.vertices.reserve(additional);
+        self.edges.reserve(additional);
+    }
+
+    /// Shrinks the capacity of the vertices and of every adjacency list as much as possible
+    pub fn shrink_to_fit(&mut self) {
+        self.vertices.shrink_to_fit();
+        self.edges.shrink_to_fit();
+        for edges in &mut self.edges {
+            edges.shrink_to_fit();
+        }
+    }
+
+    /// Returns a rough estimate of the heap memory used by the graph in bytes.
+    ///
+    /// This accounts for the allocated capacity of the vertices, the per-vertex adjacency lists
+    /// and the edges stored in them, but not for memory owned by the vertices or edges themselves.
+    pub fn estimated_memory_bytes(&self) -> usize {
+        let vertices = self.vertices.capacity() * std::mem::size_of::<V>();
+        let adjacency = self.edges.capacity() * std::mem::size_of::<Vec<E>>();
+        let edges = self
+            .edges
+            .iter()
+            .map(|edges| edges.capacity() * std::mem::size_of::<E>())
+            .sum::<usize>();
+        vertices + adjacency + edges
+    }
+}
+
+impl<V, E> Graph<V, E>
+where
+    E: Edge,
+{
+    /// Reverses the direction of every edge in place, so that an edge from `u` to `v` becomes
+    /// an edge from `v` to `u`. Everything else about an edge, like its weight, stays the same.
+    pub fn reverse_in_place(&mut self) {
+        let edges = std::mem::take(&mut self.edges);
+        self.edges = repeat_with(Vec::new).take(self.vertices.len()).collect();
+        for (from, edges) in edges.into_iter().enumerate() {
+            for mut edge in edges {
+                let to = edge.pointing_to();
+                edge.redirect(Handle(from));
+                self.edges[to.0].push(edge);
+            }
+        }
+    }
+
+    /// Removes `vertex` and all edges from and to it.
+    ///
+    /// The vertices after `vertex` move down by one, so their handles change. The returned [HandleRemap]
+    /// maps the old handles to the new ones.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `vertex` is not a valid handle
+    pub fn remove_vertex(&mut self, vertex: Handle) -> HandleRemap {
+        assert!(self.is_valid_handle(vertex), "vertex does not exist");
+        let mut keep = vec![true; self.size()];
+        keep[vertex.0] = false;
+        self.retain_by_handle(&keep)
+    }
+
+    /// Removes every vertex whose value does not satisfy `keep`, together with all edges from and to it.
+    ///
+    /// The remaining vertices keep their relative order. The returned [HandleRemap] maps the old handles to the new ones.
+    pub fn retain_vertices(&mut self, keep: impl Fn(&V) -> bool) -> HandleRemap {
+        let keep: Vec<_> = self.vertices.iter().map(keep).collect();
+        self.retain_by_handle(&keep)
+    }
+
+    /// Removes every vertex `v` with `keep[v] == false` and all edges from and to it
+    pub(crate) fn retain_by_handle(&mut self, keep: &[bool]) -> HandleRemap {
+        let mut kept = 0;
+        let remap: Vec<_> = keep
+            .iter()
+            .map(|&keep| {
+                keep.then(|| {
+                    kept += 1;
+                    Handle(kept - 1)
+                })
+            })
+            .collect();
+
+        let vertices = std::mem::take(&mut self.vertices);
+        let edges = std::mem::take(&mut self.edges);
+        for ((value, mut edges), &keep) in vertices.into_iter().zip(edges).zip(keep) {
+            if !keep {
+                continue;
+            }
+            edges.retain_mut(|edge| match remap[edge.pointing_to().0] {
+                Some(to) => {
+                    edge.redirect(to);
+                    true
+                }
+                None => false,
+            });
+            self.vertices.push(value);
+            self.edges.push(edges);
+        }
+
+        HandleRemap(remap)
+    }
+
+    /// Reorders the vertices according to `compare`, moving their edges along with them.
+    /// Vertices that compare equal keep their relative order, as do the outgoing edges of every vertex.
+    ///
+    /// Returns the mapping from the old handles to the new ones, which is defined for every vertex.
+    ///
+    /// ```
+    /// # use comtesse::{unweighted::Unweighted, HasEdge};
+    /// let mut graph: Unweighted<_> = ['c', 'a', 'b'].into_iter().collect();
+    /// let c = graph.get_vertex('c').unwrap();
+    /// let a = graph.get_vertex('a').unwrap();
+    /// graph.add_edge(c, a);
+    ///
+    /// let remap = graph.sort_vertices_by(|u, v| u.cmp(v));
+    /// let (c, a) = (remap.map(c).unwrap(), remap.map(a).unwrap());
+    /// assert_eq!(graph.vertex_value(a), &'a');
+    /// assert!(graph.has_edge(c, a));
+    /// ```
+    pub fn sort_vertices_by(
+        &mut self,
+        compare: impl Fn(&V, &V) -> std::cmp::Ordering,
+    ) -> HandleRemap {
+        let mut order: Vec<_> = (0..self.vertices.len()).collect();
+        order.sort_by(|&u, &v| compare(&self.vertices[u], &self.vertices[v]));
+
+        let mut remap = vec![None; order.len()];
+        for (new, &old) in order.iter().enumerate() {
+            remap[old] = Some(Handle(new));
+        }
+
+        let mut vertices: Vec<_> = std::mem::take(&mut self.vertices)
+            .into_iter()
+            .map(Some)
+            .collect();
+        let mut edges: Vec<_> = std::mem::take(&mut self.edges)
+            .into_iter()
+            .map(Some)
+            .collect();
+        for old in order {
+            let mut outgoing = edges[old]
+                .take()
+                .expect("every vertex appears once in order");
+            for edge in &mut outgoing {
+                edge.redirect(remap[edge.pointing_to().0].expect("every vertex is remapped"));
+            }
+            self.vertices.push(
+                vertices[old]
+                    .take()
+                    .expect("every vertex appears once in order"),
+            );
+            self.edges.push(outgoing);
+        }
+
+        HandleRemap(remap)
+    }
+
+    /// Returns whether every vertex has an edge from or to it, indexed by handle
+    fn has_incident_edge(&self) -> Vec<bool> {
+        let mut incident: Vec<_> = self.edges.iter().map(|edges| !edges.is_empty()).collect();
+        for edge in self.edges.iter().flatten() {
+            incident[edge.pointing_to().0] = true;
+        }
+        incident
+    }
+
+    /// Returns the vertices without any incoming or outgoing edge, sorted by handle
+    pub fn isolated_vertices(&self) -> Vec<Handle> {
+        let incident = self.has_incident_edge();
+        (0..self.size())
+            .filter(|&vertex| !incident[vertex])
+            .map(Handle)
+            .collect()
+    }
+
+    /// Removes all [isolated vertices](Graph::isolated_vertices). The returned [HandleRemap] maps
+    /// the old handles to the new ones.
+    pub fn remove_isolated(&mut self) -> HandleRemap {
+        let incident = self.has_incident_edge();
+        self.retain_by_handle(&incident)
+    }
+
+    /// Moves all vertices and edges of `other` into this graph, returning the new handles of
+    /// the vertices of `other` in their original order.
+    ///
+    /// The vertices of `other` are added after the existing vertices, so a vertex of `other` with
+    /// the handle `h` ends up at `h.index() + self.size()` and the edges of `other` are shifted accordingly.
+    /// No vertices are merged, even if they have the same value.
+    pub fn append(&mut self, other: Graph<V, E>) -> Vec<Handle> {
+        let offset = self.vertices.len();
+        self.vertices.extend(other.vertices);
+        self.edges.extend(other.edges.into_iter().map(|mut edges| {
+            for edge in &mut edges {
+                let to = edge.pointing_to();
+                edge.redirect(Handle(to.0 + offset));
+            }
+            edges
+        }));
+        (offset..self.vertices.len()).map(Handle).collect()
+    }
 }
 
 impl<V, E> FromIterator<V> for Graph<V, E> {
@@ -88,4 +482,295 @@ where
             .find(|(_, vertex)| **vertex == vertex_value)
             .map(|(i, _)| Handle(i))
     }
+
+    /// Returns a handle to the first vertex containing `vertex_value`,
+    /// adding a new vertex with that value if no such vertex exists
+    pub fn get_or_add_vertex(&mut self, vertex_value: V) -> Handle {
+        match self
+            .vertices
+            .iter()
+            .position(|vertex| *vertex == vertex_value)
+        {
+            Some(i) => Handle(i),
+            None => self.add_vertex(vertex_value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Handle, HandleMap, HandleRemap};
+    use crate::{unweighted::Unweighted, weighted::Weighted, HasEdge, SizeMismatch};
+
+    #[test]
+    fn handle_index() {
+        let graph: Unweighted<_> = ('a'..='e').collect();
+        let d = graph.get_vertex('d').unwrap();
+
+        assert_eq!(d.index(), 3);
+        assert_eq!(Handle::from_index(d.index()), d);
+        assert!(graph.is_valid_handle(Handle::from_index(4)));
+        assert!(!graph.is_valid_handle(Handle::from_index(5)));
+    }
+
+    #[test]
+    fn estimated_memory() {
+        let mut graph = Unweighted::new();
+        let empty = graph.estimated_memory_bytes();
+
+        let a = graph.add_vertex(1u64);
+        let b = graph.add_vertex(2);
+        let with_vertices = graph.estimated_memory_bytes();
+        assert!(with_vertices > empty);
+
+        graph.add_edge(a, b);
+        graph.add_edge(b, a);
+        assert!(graph.estimated_memory_bytes() > with_vertices);
+    }
+
+    #[test]
+    fn shrink_to_fit() {
+        let mut graph: Unweighted<_> = (0..10).collect();
+        graph.construct_edges_from(|&u, &v| u < v);
+        graph.reserve(100);
+        assert!(graph.vertices.capacity() >= 110);
+
+        for vertex in 0..10 {
+            graph.edges[vertex].reserve(10);
+        }
+        let reserved = graph.estimated_memory_bytes();
+
+        graph.shrink_to_fit();
+        assert!(graph.vertices.capacity() < 110);
+        assert!(graph.edges.capacity() < 110);
+        assert!(graph.estimated_memory_bytes() < reserved);
+        assert_eq!(graph.size(), 10);
+        assert_eq!(graph.num_edges(), 45);
+    }
+
+    #[test]
+    fn reverse_in_place() {
+        let mut graph: Unweighted<_> = (1..=6).collect();
+        graph.construct_edges_from(|&u, &v| u != v && v % u == 0);
+        let original: Vec<_> = graph.edges.clone();
+        let one = graph.get_vertex(1).unwrap();
+        let four = graph.get_vertex(4).unwrap();
+
+        graph.reverse_in_place();
+        assert!(graph.has_edge(four, one));
+        assert!(!graph.has_edge(one, four));
+        assert_eq!(graph.num_edges(), 8);
+
+        graph.reverse_in_place();
+        assert_eq!(graph.edges, original);
+
+        let mut weighted: Weighted<_, u32> = (1..=3).collect();
+        weighted.construct_edges_from(|&u, &v| (u < v).then_some(u + v));
+        weighted.reverse_in_place();
+        assert_eq!(weighted.get_edge(Handle(2), Handle(0)), Some(4));
+        assert_eq!(weighted.get_edge(Handle(0), Handle(2)), None);
+    }
+
+    #[test]
+    fn find_vertices() {
+        let graph: Unweighted<_> = (1..=10).collect();
+
+        assert_eq!(graph.count_vertices(|v| v % 2 == 0), 5);
+        assert_eq!(
+            graph.find_vertices(|v| v % 2 == 0),
+            [Handle(1), Handle(3), Handle(5), Handle(7), Handle(9)]
+        );
+        assert_eq!(graph.count_vertices(|&v| v > 10), 0);
+    }
+
+    #[test]
+    fn append() {
+        let mut first: Unweighted<_> = (0..3).collect();
+        first.construct_edges_from(|&u, &v| v == u + 1);
+        let mut second: Weighted<_, u32> = (0..3).collect();
+        second.construct_edges_from(|&u, &v| (v == (u + 1) % 3).then_some(u + 1));
+        let mut second_unweighted: Unweighted<_> = (3..6).collect();
+        second_unweighted.construct_edges_from(|&u, &v| v == (u + 1) % 3 + 3);
+
+        let handles = first.append(second_unweighted);
+        assert_eq!(handles, [Handle(3), Handle(4), Handle(5)]);
+        assert_eq!(first.size(), 6);
+        assert_eq!(first.num_edges(), 5);
+        assert!(first.has_edge(Handle(0), Handle(1)));
+        assert!(first.has_edge(Handle(5), Handle(3)));
+        assert!(!first.has_edge(Handle(2), Handle(0)));
+
+        let mut copy: Weighted<_, u32> = (0..3).collect();
+        copy.construct_edges_from(|&u, &v| (v == (u + 1) % 3).then_some(u + 1));
+        let handles = second.append(copy);
+        assert_eq!(second.size(), 6);
+        assert_eq!(second.get_edge(handles[2], handles[0]), Some(3));
+        assert_eq!(second.get_edge(Handle(2), Handle(0)), Some(3));
+    }
+
+    #[test]
+    fn remove_vertex() {
+        let mut graph: Unweighted<_> = ('a'..='e').collect();
+        graph.construct_edges_from(|&u, &v| u != v);
+        let old: Vec<_> = ('a'..='e').map(|v| graph.get_vertex(v).unwrap()).collect();
+
+        let remap = graph.remove_vertex(old[1]);
+        assert_eq!(graph.size(), 4);
+        assert_eq!(graph.num_edges(), 12);
+        assert_eq!(remap.len(), 5);
+        assert_eq!(remap.map(old[1]), None);
+        assert_eq!(remap.map(Handle(5)), None);
+        for (old, value) in old.iter().zip('a'..='e').filter(|&(_, v)| v != 'b') {
+            let new = remap.map(*old).unwrap();
+            assert_eq!(*graph.vertex_value(new), value);
+        }
+        assert!(graph.has_edge(remap.map(old[4]).unwrap(), remap.map(old[0]).unwrap()));
+
+        let mut weighted: Weighted<_, u32> = (0..6).collect();
+        weighted.construct_edges_from(|&u, &v| (v == (u + 1) % 6).then_some(u));
+        let remap = weighted.retain_vertices(|v| v % 3 != 0);
+        assert_eq!(
+            remap,
+            HandleRemap(vec![
+                None,
+                Some(Handle(0)),
+                Some(Handle(1)),
+                None,
+                Some(Handle(2)),
+                Some(Handle(3))
+            ])
+        );
+        assert_eq!(weighted.num_edges(), 2);
+        assert_eq!(weighted.get_edge(Handle(0), Handle(1)), Some(1));
+        assert_eq!(weighted.get_edge(Handle(2), Handle(3)), Some(4));
+    }
+
+    #[test]
+    fn handle_map() {
+        let mut graph: Unweighted<_> = (0..4).collect();
+        graph.construct_edges_from(|&u, &v| v == u + 1);
+
+        let mut distance = HandleMap::filled(&graph, None);
+        assert_eq!(distance.len(), 4);
+        for (i, vertex) in (0..4).map(Handle).enumerate() {
+            *distance.get_mut(vertex) = Some(i);
+        }
+
+        assert_eq!(*distance.get(Handle(2)), Some(2));
+        assert_eq!(
+            distance.iter().map(|(_, &d)| d).collect::<Vec<_>>(),
+            graph.bfs_distances(Handle(0))
+        );
+        assert_eq!(distance.into_vec(), [Some(0), Some(1), Some(2), Some(3)]);
+    }
+
+    #[test]
+    fn isolated_vertices() {
+        let mut graph: Unweighted<_> = ('a'..='e').collect();
+        graph.construct_edges_from(|&u, &v| matches!((u, v), ('a', 'b') | ('e', 'd') | ('e', 'e')));
+        let d = graph.get_vertex('d').unwrap();
+
+        assert_eq!(graph.isolated_vertices(), [Handle(2)]);
+
+        let remap = graph.remove_isolated();
+        assert_eq!(graph.size(), 4);
+        assert_eq!(graph.num_edges(), 3);
+        assert_eq!(remap.map(Handle(2)), None);
+        assert_eq!(remap.map(d), Some(Handle(2)));
+        assert_eq!(*graph.vertex_value(Handle(2)), 'd');
+        assert!(graph.has_edge(Handle(3), Handle(2)));
+        assert!(graph.isolated_vertices().is_empty());
+    }
+
+    #[test]
+    fn clone() {
+        let mut original: Weighted<_, u32> = (0..4).collect();
+        original.construct_edges_from(|&u, &v| (v == u + 1).then_some(u + v));
+
+        let mut snapshot = original.clone();
+        snapshot.remove_vertex(Handle(1));
+        snapshot.add_edge(Handle(0), Handle(0), 9);
+
+        assert_eq!(original.size(), 4);
+        assert_eq!(original.num_edges(), 3);
+        assert_eq!(original.get_edge(Handle(0), Handle(1)), Some(1));
+        assert_eq!(original.get_edge(Handle(0), Handle(0)), None);
+        assert_eq!(snapshot.size(), 3);
+        assert_eq!(snapshot.num_edges(), 2);
+    }
+
+    #[test]
+    fn relabel() {
+        let mut graph: Unweighted<_> = (1..=6).collect();
+        graph.construct_edges_from(|&u, &v| u != v && v % u == 0);
+
+        let parity = graph
+            .relabel(&["odd", "even", "odd", "even", "odd", "even"])
+            .unwrap();
+        assert_eq!(parity.size(), graph.size());
+        assert_eq!(parity.num_edges(), graph.num_edges());
+        for from in (0..6).map(Handle) {
+            assert_eq!(parity.neighbors(from), graph.neighbors(from));
+        }
+        assert_eq!(*parity.vertex_value(Handle(3)), "even");
+
+        assert_eq!(
+            graph.relabel(&[0; 7]).err(),
+            Some(SizeMismatch {
+                expected: 6,
+                found: 7
+            })
+        );
+    }
+
+    #[test]
+    fn has_any_edge() {
+        let mut graph: Unweighted<_> = (0..4).collect();
+        assert!(!graph.has_any_edge());
+
+        graph.add_edge(Handle(3), Handle(1));
+        assert!(graph.has_any_edge());
+    }
+
+    #[test]
+    fn clear_edges() {
+        let mut graph: Unweighted<_> = (0..6).collect();
+        graph.construct_edges_from(|&u, &v| u < v);
+        assert_eq!(graph.num_edges(), 15);
+
+        graph.clear_edges();
+        assert_eq!(graph.num_edges(), 0);
+        assert_eq!(graph.size(), 6);
+        assert_eq!(graph.get_vertex(4), Some(Handle(4)));
+
+        graph.construct_edges_from(|&u, &v| v == u + 1);
+        assert_eq!(graph.num_edges(), 5);
+        assert!(graph.has_edge(Handle(2), Handle(3)));
+    }
+
+    #[test]
+    fn sort_vertices_by() {
+        let mut graph: Weighted<_, u32> = ['d', 'b', 'e', 'a', 'c'].into_iter().collect();
+        graph.construct_edges_from(|&u, &v| (u < v).then(|| u32::from(v) - u32::from(u)));
+        let mut edges_before: Vec<_> = graph
+            .edges_with_values()
+            .map(|(&u, &v, weight)| (u, v, weight))
+            .collect();
+        let d = graph.get_vertex('d').unwrap();
+
+        let remap = graph.sort_vertices_by(|u, v| u.cmp(v));
+        assert_eq!(remap.map(d), Some(Handle(3)));
+        for (i, value) in ('a'..='e').enumerate() {
+            assert_eq!(*graph.vertex_value(Handle(i)), value);
+        }
+
+        let mut edges_after: Vec<_> = graph
+            .edges_with_values()
+            .map(|(&u, &v, weight)| (u, v, weight))
+            .collect();
+        edges_before.sort_unstable();
+        edges_after.sort_unstable();
+        assert_eq!(edges_before, edges_after);
+        assert_eq!(graph.get_edge(Handle(0), Handle(4)), Some(4));
+    }
 }
@@ -0,0 +1,120 @@
+//! A Graph with labeled edges, containing vertices of type `V`
+
+use crate::{
+    graph::{Edge, Graph, Handle},
+    HasEdge,
+};
+
+/// An edge carrying a label of type `L`, for example a name or a road type
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LabeledEdge<L> {
+    to: Handle,
+    label: L,
+}
+
+impl<L> LabeledEdge<L> {
+    /// Returns the label associated with the edge
+    #[inline]
+    pub fn label(&self) -> &L {
+        &self.label
+    }
+
+    /// Returns a handle to the vertex being pointed to
+    #[inline]
+    pub fn pointing_to(&self) -> Handle {
+        self.to
+    }
+}
+
+impl<L> Edge for LabeledEdge<L> {
+    fn pointing_to(&self) -> Handle {
+        self.to
+    }
+
+    fn redirect(&mut self, to: Handle) {
+        self.to = to;
+    }
+}
+
+/// A Graph containing vertices of type `V` whose edges carry labels of type `L`.
+/// An edge in this Graph is represented by [LabeledEdge]
+///
+/// ```
+/// # use comtesse::labeled::LabeledGraph;
+/// let mut graph = LabeledGraph::new();
+/// let home = graph.add_vertex("home");
+/// let work = graph.add_vertex("work");
+/// graph.add_edge(home, work, "highway");
+///
+/// assert_eq!(graph.edge_label(home, work), Some(&"highway"));
+/// assert_eq!(graph.edge_label(work, home), None);
+/// ```
+pub type LabeledGraph<V, L> = Graph<V, LabeledEdge<L>>;
+
+impl<V, L> LabeledGraph<V, L> {
+    /// Connects two vertices, as given by `from` and `to` with an edge labeled `label`
+    pub fn add_edge(&mut self, from: Handle, to: Handle, label: L) {
+        self.edges[from.0].push(LabeledEdge { to, label });
+    }
+
+    /// Returns the label of the edge from `from` to `to` if it exists.
+    /// If there are multiple edges between the two vertices, the label of the first one added is returned
+    pub fn edge_label(&self, from: Handle, to: Handle) -> Option<&L> {
+        self.edges[from.0]
+            .iter()
+            .find(|edge| edge.to == to)
+            .map(|edge| &edge.label)
+    }
+
+    /// Returns all outgoing edges of `vertex`
+    pub fn neighbors(&self, vertex: Handle) -> &[LabeledEdge<L>] {
+        &self.edges[vertex.0]
+    }
+}
+
+impl<V, L> HasEdge for LabeledGraph<V, L> {
+    /// Returns whether the edge starting at `from` and going to `to` exists in the graph
+    fn has_edge(&self, from: Handle, to: Handle) -> bool {
+        self.edges[from.0].iter().any(|edge| edge.to == to)
+    }
+
+    fn connected_neighbors<'a>(&'a self, vertex: Handle) -> Box<dyn Iterator<Item = Handle> + 'a> {
+        Box::new(self.edges[vertex.0].iter().map(|edge| edge.to))
+    }
+
+    fn neighbor_count(&self, vertex: Handle) -> usize {
+        self.edges[vertex.0].len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LabeledGraph;
+    use crate::{graph::Handle, HasEdge};
+
+    #[test]
+    fn string_labels() {
+        let mut graph: LabeledGraph<_, String> = ['a', 'b', 'c'].into_iter().collect();
+        let (a, b, c) = (Handle(0), Handle(1), Handle(2));
+        graph.add_edge(a, b, "footpath".to_string());
+        graph.add_edge(b, c, "road".to_string());
+        graph.add_edge(a, c, "rail".to_string());
+
+        assert_eq!(graph.edge_label(a, b).map(String::as_str), Some("footpath"));
+        assert_eq!(graph.edge_label(b, c).map(String::as_str), Some("road"));
+        assert_eq!(graph.edge_label(a, c).map(String::as_str), Some("rail"));
+        assert_eq!(graph.edge_label(c, a), None);
+
+        assert!(graph.has_edge(a, c));
+        assert_eq!(graph.num_edges(), 3);
+        assert_eq!(graph.neighbors(a)[1].label(), "rail");
+        assert_eq!(graph.shortest_distance_unweighted(a, c), Some(1));
+
+        graph.remove_vertex(b);
+        assert_eq!(graph.num_edges(), 1);
+        assert_eq!(
+            graph.edge_label(a, Handle(1)).map(String::as_str),
+            Some("rail")
+        );
+    }
+}
@@ -3,7 +3,7 @@
 //! Utility crate to handle common tasks that require graphs
 //!
 //! ```
-//! use comtesse::{unweighted::Unweighted, HasEdge};
+//! use comtesse::unweighted::Unweighted;
 //!
 //! let mut graph = Unweighted::new();
 //! // insert the numbers 1 to 10 as vertices
@@ -17,10 +17,7 @@
 //! graph.construct_edges_from(|&u, &v| u != v && (u + v) % 10 == 0);
 //!
 //! // (1, 9) should be an edge, since (1 + 9) % 10 == 0
-//! assert!(graph.has_edge(
-//!     graph.get_vertex(1).unwrap(),
-//!     graph.get_vertex(9).unwrap()
-//! ));
+//! assert!(graph.has_edge_between_values(&1, &9));
 //! ```
 //!
 //!
@@ -63,10 +60,17 @@ use std::{borrow::Cow, fmt::Write};
 use graph::Handle;
 
 mod algorithms;
+pub mod dimacs;
+mod export;
 pub mod graph;
+pub mod labeled;
+mod macros;
 pub mod unweighted;
+pub mod util;
 pub mod weighted;
 
+pub use algorithms::DfsOrder;
+
 pub(crate) trait DumpGraphviz {
     fn dump(&self, output: &mut dyn Write) -> Result<(), std::fmt::Error>;
 }
@@ -79,6 +83,85 @@ pub trait HasEdge {
     /// should return iterator to all neighbors of `vertex`
     // TODO: dynamic dispatch probably not very good
     fn connected_neighbors<'a>(&'a self, vertex: Handle) -> Box<dyn Iterator<Item = Handle> + 'a>;
+
+    /// returns an owned list of all neighbors of `vertex`
+    ///
+    /// Unlike [HasEdge::connected_neighbors], this does not borrow the graph,
+    /// so the graph can be modified while iterating over the result
+    fn neighbors_snapshot(&self, vertex: Handle) -> Vec<Handle> {
+        self.connected_neighbors(vertex).collect()
+    }
+
+    /// returns the number of neighbors of `vertex`, counting multiple edges to the same vertex separately
+    fn neighbor_count(&self, vertex: Handle) -> usize {
+        self.connected_neighbors(vertex).count()
+    }
+}
+
+/// Error returned by shortest path algorithms when a negative cycle prevents shortest paths from existing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NegativeCycle;
+
+impl std::fmt::Display for NegativeCycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "graph contains a negative cycle")
+    }
+}
+
+impl std::error::Error for NegativeCycle {}
+
+/// Error returned by algorithms that require the graph to be acyclic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CycleError;
+
+impl std::fmt::Display for CycleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "graph contains a cycle")
+    }
+}
+
+impl std::error::Error for CycleError {}
+
+/// Error returned when a slice with one entry per vertex has the wrong length
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeMismatch {
+    /// the number of vertices in the graph
+    pub expected: usize,
+    /// the length of the given slice
+    pub found: usize,
+}
+
+impl std::fmt::Display for SizeMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "expected {} entries, one for every vertex, but found {}",
+            self.expected, self.found
+        )
+    }
+}
+
+impl std::error::Error for SizeMismatch {}
+
+/// Escapes the characters that have a special meaning in XML
+pub(crate) fn make_xml_safe(input: &str) -> Cow<'_, str> {
+    if let Some(ok_until) = input.find(['&', '<', '>', '"', '\'']) {
+        let mut out = String::from(&input[..ok_until]);
+        out.reserve(input.len() - ok_until);
+        for ch in input[ok_until..].chars() {
+            match ch {
+                '&' => out.push_str("&amp;"),
+                '<' => out.push_str("&lt;"),
+                '>' => out.push_str("&gt;"),
+                '"' => out.push_str("&quot;"),
+                '\'' => out.push_str("&apos;"),
+                _ => out.push(ch),
+            }
+        }
+        Cow::Owned(out)
+    } else {
+        Cow::Borrowed(input)
+    }
 }
 
 #[cfg(test)]
@@ -175,6 +258,22 @@ mod tests {
 
         assert!(length > 4);
     }
+
+    #[test]
+    fn remove_edges_from_snapshot() {
+        let mut graph: Unweighted<_> = (1..=5).collect();
+        graph.construct_edges_from(|&from, &to| from != to && (from == 1 || to == 1));
+
+        let one = graph.get_vertex(1).unwrap();
+        assert_eq!(graph.neighbors(one).len(), 4);
+
+        for neighbor in graph.neighbors_snapshot(one) {
+            graph.remove_edge(one, neighbor);
+        }
+
+        assert!(graph.neighbors(one).is_empty());
+        assert_eq!(graph.num_edges(), 4);
+    }
 }
 
 pub(crate) fn make_safer(input: &str) -> Cow<'_, str> {
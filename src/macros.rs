@@ -0,0 +1,123 @@
+//! declarative graph construction
+
+/// Constructs a graph from a list of edges `{from} -> {to};`
+///
+/// Vertices are deduplicated by value, so an endpoint that appears in several
+/// edges refers to the same vertex. Single vertices without edges can be added
+/// with `{vertex};`
+///
+/// Edges written as `{from} -{weight}-> {to};` carry a weight and produce a
+/// [crate::weighted::Weighted] graph instead of an [crate::unweighted::Unweighted] one.
+/// Both kinds of edges can not be mixed in one graph. If the kind can not be inferred
+/// (e.g. because there are no edges), the type can be given with a leading `type T;`
+///
+/// Every edge or vertex is handled by one recursive macro call, so graphs with more than 125
+/// entries exceed the default recursion limit of 128. Raise it in the crate using the macro
+/// with `#![recursion_limit = "256"]` (or higher) at the top of its `lib.rs` or `main.rs`.
+///
+/// ```
+/// use comtesse::{graph, HasEdge};
+///
+/// let graph = graph! {
+///     {"hello"} -> {"bye"};
+///     {"hello"} -> {"no"};
+///     {"lonely"};
+/// };
+///
+/// let hello = graph.get_vertex("hello").unwrap();
+/// assert_eq!(graph.size(), 4);
+/// assert_eq!(graph.neighbors(hello).len(), 2);
+/// assert!(graph.has_edge(hello, graph.get_vertex("bye").unwrap()));
+///
+/// let triangle = graph! {
+///     {'a'} -{1.5}-> {'b'};
+///     {'b'} -{2.0}-> {'c'};
+///     {'c'} -{0.5}-> {'a'};
+/// };
+/// let a = triangle.get_vertex('a').unwrap();
+/// let b = triangle.get_vertex('b').unwrap();
+/// assert_eq!(triangle.get_edge(a, b), Some(1.5));
+///
+/// let lonely = graph! {
+///     type comtesse::weighted::Weighted<_, i32>;
+///     {1};
+/// };
+/// assert_eq!(lonely.num_edges(), 0);
+/// ```
+#[macro_export]
+macro_rules! graph {
+    (type $graph:ty; $($rest:tt)*) => {{
+        let mut graph: $graph = $crate::graph::Graph::new();
+        $crate::graph_inner!(graph; $($rest)*);
+        graph
+    }};
+    ($($rest:tt)*) => {{
+        let mut graph = $crate::graph::Graph::new();
+        $crate::graph_inner!(graph; $($rest)*);
+        graph
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! graph_inner {
+    ($graph:ident;) => {};
+    ($graph:ident; {$from:expr} -> {$to:expr}; $($rest:tt)*) => {
+        let from = $graph.get_or_add_vertex($from);
+        let to = $graph.get_or_add_vertex($to);
+        $crate::unweighted::Unweighted::add_edge(&mut $graph, from, to);
+        $crate::graph_inner!($graph; $($rest)*);
+    };
+    ($graph:ident; {$from:expr} -{$weight:expr}-> {$to:expr}; $($rest:tt)*) => {
+        let from = $graph.get_or_add_vertex($from);
+        let to = $graph.get_or_add_vertex($to);
+        $crate::weighted::Weighted::add_edge(&mut $graph, from, to, $weight);
+        $crate::graph_inner!($graph; $($rest)*);
+    };
+    ($graph:ident; {$vertex:expr}; $($rest:tt)*) => {
+        $graph.get_or_add_vertex($vertex);
+        $crate::graph_inner!($graph; $($rest)*);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{unweighted::Unweighted, weighted::Weighted, HasEdge};
+
+    #[test]
+    fn deduplicates_vertices() {
+        let graph: Unweighted<_> = graph! {
+            {"hello"} -> {"bye"};
+            {"hello"} -> {"no"};
+            {"no"} -> {"hello"};
+        };
+
+        assert_eq!(graph.size(), 3);
+        assert_eq!(graph.num_edges(), 3);
+
+        let hello = graph.get_vertex("hello").unwrap();
+        let no = graph.get_vertex("no").unwrap();
+        assert_eq!(graph.neighbors(hello).len(), 2);
+        assert!(graph.has_edge(no, hello));
+    }
+
+    #[test]
+    fn weighted_triangle() {
+        let graph: Weighted<_, u32> = graph! {
+            {'a'} -{3}-> {'b'};
+            {'b'} -{4}-> {'c'};
+            {'c'} -{5}-> {'a'};
+        };
+
+        assert_eq!(graph.size(), 3);
+        assert_eq!(graph.num_edges(), 3);
+
+        let a = graph.get_vertex('a').unwrap();
+        let b = graph.get_vertex('b').unwrap();
+        let c = graph.get_vertex('c').unwrap();
+        assert_eq!(graph.get_edge(a, b), Some(3));
+        assert_eq!(graph.get_edge(b, c), Some(4));
+        assert_eq!(graph.get_edge(c, a), Some(5));
+        assert_eq!(graph.get_edge(a, c), None);
+    }
+}
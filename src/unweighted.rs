@@ -1,10 +1,13 @@
 //! A unweighted graph, containing elements of type `V`
 
-use std::fmt::{Debug, Write};
+use std::{
+    collections::{hash_map::RandomState, HashMap, HashSet},
+    fmt::{Debug, Display, Write},
+    hash::{BuildHasher, Hash},
+};
 
 use crate::{
-    graph::{Graph, Handle},
-    make_safer,
+    graph::{Graph, Handle, HandleRemap},
     weighted::Weighted,
     DumpGraphviz, HasEdge,
 };
@@ -46,6 +49,41 @@ use crate::{
 /// ```
 pub type Unweighted<V> = Graph<V, Handle>;
 
+/// Summary of the edges created by [Unweighted::construct_edges_from_checked]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ConstructionReport {
+    /// number of edges that were created
+    pub edges: usize,
+    /// number of created edges going from a vertex to itself
+    pub self_loops: usize,
+    /// number of pairs of distinct vertices `u` and `v` where both `(u, v)` and `(v, u)` were created
+    pub symmetric_pairs: usize,
+}
+
+/// Error returned by [Unweighted::contract_matching] if the given pairs are not a matching of the graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchingError {
+    /// The pair is not connected by an edge in either direction
+    NotAnEdge(Handle, Handle),
+    /// The vertex appears in more than one pair
+    SharedVertex(Handle),
+}
+
+impl Display for MatchingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatchingError::NotAnEdge(a, b) => {
+                write!(f, "vertices {} and {} are not adjacent", a.0, b.0)
+            }
+            MatchingError::SharedVertex(vertex) => {
+                write!(f, "vertex {} appears in more than one pair", vertex.0)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MatchingError {}
+
 impl<V> Unweighted<V> {
     /// Connects two vertices, as given by `from` and `to`
     pub fn add_edge(&mut self, from: Handle, to: Handle) {
@@ -53,6 +91,24 @@ impl<V> Unweighted<V> {
         self.edges[from].push(to);
     }
 
+    /// Adds all `edges` at once after checking that all their endpoints are vertices of the graph.
+    ///
+    /// Returns the first invalid handle as an error, in which case no edge is added.
+    pub fn add_edges(&mut self, edges: &[(Handle, Handle)]) -> Result<(), Handle> {
+        if let Some(&invalid) = edges
+            .iter()
+            .flat_map(|(from, to)| [from, to])
+            .find(|&&vertex| !self.is_valid_handle(vertex))
+        {
+            return Err(invalid);
+        }
+
+        for &(from, to) in edges {
+            self.add_edge(from, to);
+        }
+        Ok(())
+    }
+
     /// Constructs edges that satisfy the given `condition`
     pub fn construct_edges_from<F>(&mut self, condition: F)
     where
@@ -67,6 +123,42 @@ impl<V> Unweighted<V> {
         }
     }
 
+    /// Constructs edges that satisfy the given `condition` exactly like [Unweighted::construct_edges_from],
+    /// but also reports how many edges, self loops and symmetric pairs were created.
+    ///
+    /// This is useful to validate a `condition`: a symmetric condition creates an edge in both directions for
+    /// every pair, which is what undirected graphs need but is often unintended for directed graphs.
+    ///
+    /// ```
+    /// # use comtesse::unweighted::Unweighted;
+    /// let mut graph: Unweighted<_> = (1..=4).collect();
+    /// let report = graph.construct_edges_from_checked(|&u, &v| (u + v) % 2 == 0);
+    /// assert_eq!(report.self_loops, 4);
+    /// assert_eq!(report.symmetric_pairs, 2);
+    /// ```
+    pub fn construct_edges_from_checked<F>(&mut self, condition: F) -> ConstructionReport
+    where
+        F: Fn(&V, &V) -> bool,
+    {
+        let mut created = HashSet::new();
+        let mut report = ConstructionReport::default();
+        for u in 0..self.vertices.len() {
+            for v in 0..self.vertices.len() {
+                if condition(&self.vertices[u], &self.vertices[v]) {
+                    self.add_edge(Handle(u), Handle(v));
+                    report.edges += 1;
+                    if u == v {
+                        report.self_loops += 1;
+                    } else if created.contains(&(v, u)) {
+                        report.symmetric_pairs += 1;
+                    }
+                    created.insert((u, v));
+                }
+            }
+        }
+        report
+    }
+
     /// returns a list of neighbors of `vertex` in the graph
     pub fn neighbors(&self, vertex: Handle) -> &[Handle] {
         let vertex = vertex.0;
@@ -91,34 +183,369 @@ impl<V> Unweighted<V> {
         };
         self.edges[from.0].swap_remove(to);
     }
+
+    /// Removes every edge for which `keep(from, to)` returns `false`
+    pub fn retain_edges(&mut self, keep: impl Fn(Handle, Handle) -> bool) {
+        for (from, edges) in self.edges.iter_mut().enumerate() {
+            edges.retain(|&to| keep(Handle(from), to));
+        }
+    }
+
+    /// Returns an iterator over all edges as pairs of the values of their endpoints,
+    /// ordered by the handle of the starting vertex
+    ///
+    /// ```
+    /// # use comtesse::unweighted::Unweighted;
+    /// let mut graph: Unweighted<_> = (1..=10).collect();
+    /// graph.construct_edges_from(|&u, &v| u != v && (u + v) % 10 == 0);
+    ///
+    /// for (u, v) in graph.edges_with_values() {
+    ///     println!("{u} -> {v}");
+    /// }
+    ///
+    /// let edges: Vec<_> = graph.edges_with_values().take(3).collect();
+    /// assert_eq!(edges, [(&1, &9), (&2, &8), (&3, &7)]);
+    /// ```
+    pub fn edges_with_values(&self) -> impl Iterator<Item = (&V, &V)> {
+        self.edges
+            .iter()
+            .enumerate()
+            .flat_map(move |(from, edges)| {
+                edges
+                    .iter()
+                    .map(move |&to| (&self.vertices[from], &self.vertices[to.0]))
+            })
+    }
+
+    /// Contracts each edge `(a, b)` of `matching`, merging `b` into `a`.
+    ///
+    /// The merged vertex keeps the value of `a` and all edges of `a` and `b`, except for the edges
+    /// between them, which would become self loops. Contracting can create parallel edges, e.g. when
+    /// `a` and `b` were both connected to the same vertex.
+    ///
+    /// Since removing vertices changes handles, this returns a [HandleRemap] to the new handle
+    /// of every old vertex. Returns a [MatchingError] for the first pair that is not connected by an edge
+    /// or shares a vertex with an earlier pair, in which case the graph is left unchanged.
+    pub fn contract_matching(
+        &mut self,
+        matching: &[(Handle, Handle)],
+    ) -> Result<HandleRemap, MatchingError> {
+        let mut merged_into: Vec<usize> = (0..self.size()).collect();
+        let mut matched = vec![false; self.size()];
+        for &(a, b) in matching {
+            if !self.has_edge(a, b) && !self.has_edge(b, a) {
+                return Err(MatchingError::NotAnEdge(a, b));
+            }
+            for vertex in [a, b] {
+                if matched[vertex.0] {
+                    return Err(MatchingError::SharedVertex(vertex));
+                }
+                matched[vertex.0] = true;
+            }
+            merged_into[b.0] = a.0;
+        }
+
+        let mut remap = vec![Handle(0); self.size()];
+        let mut kept = 0;
+        for vertex in 0..self.size() {
+            if merged_into[vertex] == vertex {
+                remap[vertex] = Handle(kept);
+                kept += 1;
+            }
+        }
+        for vertex in 0..self.size() {
+            remap[vertex] = remap[merged_into[vertex]];
+        }
+
+        let mut edges = vec![vec![]; kept];
+        for (from, targets) in std::mem::take(&mut self.edges).into_iter().enumerate() {
+            for to in targets {
+                if from != to.0 && remap[from] == remap[to.0] {
+                    continue;
+                }
+                edges[remap[from].0].push(remap[to.0]);
+            }
+        }
+
+        let vertices = std::mem::take(&mut self.vertices);
+        self.vertices = vertices
+            .into_iter()
+            .enumerate()
+            .filter(|&(vertex, _)| merged_into[vertex] == vertex)
+            .map(|(_, value)| value)
+            .collect();
+        self.edges = edges;
+
+        Ok(HandleRemap(remap.into_iter().map(Some).collect()))
+    }
+
+    /// Returns the line graph of this graph, which has a vertex for every edge of this graph
+    /// holding the endpoints of the edge.
+    ///
+    /// Since edges are directed, there is an edge from `(u, v)` to `(v, w)` for every pair of edges where the
+    /// first one ends where the second one starts. For an undirected graph stored with every edge in both
+    /// directions, every undirected edge becomes two vertices, and `(u, v)` is also connected to its reverse `(v, u)`.
+    /// The vertices are ordered by the handle of the starting vertex, like [Unweighted::edges_with_values].
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n + Σ in(v) out(v))` where `n` is the number of vertices
+    /// and `in(v)` and `out(v)` are the numbers of edges ending and starting at `v`
+    pub fn line_graph(&self) -> Unweighted<(Handle, Handle)> {
+        let mut first_edge = Vec::with_capacity(self.size());
+        let mut line: Unweighted<_> = Unweighted::new_with_size(self.num_edges());
+        for (from, edges) in self.edges.iter().enumerate() {
+            first_edge.push(line.size());
+            for &to in edges {
+                line.add_vertex((Handle(from), to));
+            }
+        }
+
+        for (edge, &(_, Handle(to))) in line.vertices.iter().enumerate() {
+            let successors = first_edge[to]..first_edge[to] + self.edges[to].len();
+            line.edges[edge].extend(successors.map(Handle));
+        }
+        line
+    }
+
+    /// Returns the edges of this graph that are not in `other`, ordered by handle.
+    ///
+    /// This compares the edges by handle only and ignores the vertex values, assuming that both graphs
+    /// have the same vertices in the same order (e.g. because `other` is an older snapshot of this graph).
+    /// Parallel edges are reported once. Vertices that do not exist in `other` have no edges there.
+    pub fn edge_difference(&self, other: &Self) -> Vec<(Handle, Handle)> {
+        let mut difference = vec![];
+        for (from, edges) in self.edges.iter().enumerate() {
+            let mut targets: Vec<Handle> = edges
+                .iter()
+                .copied()
+                .filter(|to| {
+                    other
+                        .edges
+                        .get(from)
+                        .is_none_or(|other| !other.contains(to))
+                })
+                .collect();
+            targets.sort_unstable();
+            targets.dedup();
+            difference.extend(targets.into_iter().map(|to| (Handle(from), to)));
+        }
+        difference
+    }
+
+    /// Returns the number of edges going out of `vertex`
+    pub fn out_degree(&self, vertex: Handle) -> usize {
+        self.edges[vertex.0].len()
+    }
+
+    /// Returns the number of edges pointing to `vertex`
+    ///
+    /// ## Running Time
+    /// This has a running time of `O(m)` where `m` is the number of edges
+    pub fn in_degree(&self, vertex: Handle) -> usize {
+        self.edges
+            .iter()
+            .flatten()
+            .filter(|&&to| to == vertex)
+            .count()
+    }
+
+    /// Returns the degree of `vertex` when treating all edges as undirected,
+    /// i.e. the sum of its in- and out-degree. A self loop counts twice.
+    ///
+    /// ## Running Time
+    /// This has a running time of `O(m)` where `m` is the number of edges
+    pub fn degree(&self, vertex: Handle) -> usize {
+        self.out_degree(vertex) + self.in_degree(vertex)
+    }
+
+    /// Returns the undirected degree (see [Unweighted::degree]) of every vertex
+    fn degrees(&self) -> Vec<usize> {
+        let mut degrees: Vec<usize> = self.edges.iter().map(|edges| edges.len()).collect();
+        for &Handle(to) in self.edges.iter().flatten() {
+            degrees[to] += 1;
+        }
+        degrees
+    }
+
+    /// Returns the largest undirected degree (see [Unweighted::degree]) of any vertex,
+    /// or `None` if the graph is empty
+    pub fn max_degree(&self) -> Option<usize> {
+        self.degrees().into_iter().max()
+    }
+
+    /// Returns the smallest undirected degree (see [Unweighted::degree]) of any vertex,
+    /// or `None` if the graph is empty
+    pub fn min_degree(&self) -> Option<usize> {
+        self.degrees().into_iter().min()
+    }
+
+    /// Returns whether all vertices have the same undirected degree (see [Unweighted::degree]).
+    ///
+    /// Note that an undirected graph that stores every edge in both directions has
+    /// twice the degrees of the corresponding undirected graph, which does not change regularity.
+    pub fn is_regular(&self) -> bool {
+        let degrees = self.degrees();
+        degrees.windows(2).all(|pair| pair[0] == pair[1])
+    }
+
+    /// Returns the fraction of possible edges that exist, `m / (n * (n - 1))` for `n` vertices and `m` edges,
+    /// or `0.0` for graphs with fewer than two vertices.
+    ///
+    /// An undirected graph that stores every edge in both directions has the same density as the
+    /// corresponding undirected graph. Self loops and multiple edges are counted as well,
+    /// so graphs containing them may have a density above `1.0`.
+    pub fn density(&self) -> f64 {
+        let n = self.size();
+        if n < 2 {
+            return 0.0;
+        }
+        self.num_edges() as f64 / (n * (n - 1)) as f64
+    }
 }
 
-impl<V: Debug> DumpGraphviz for Unweighted<V> {
-    fn dump(&self, output: &mut dyn Write) -> Result<(), std::fmt::Error> {
-        writeln!(output, "digraph {{")?;
-        for vertex in &self.vertices {
-            // TODO: vertex:? could inject stuff
-            let vertex_str = format!("{vertex:?}");
-            let vertex_str = make_safer(&vertex_str);
-            writeln!(output, "  \"{}\";", vertex_str)?;
+impl<V> Unweighted<V>
+where
+    V: Clone,
+{
+    /// Returns a copy of the graph where every edge `(u, v)` is replaced by a new vertex `w`
+    /// and the edges `(u, w)` and `(w, v)`. The value of `w` is `make_vertex(&u, &v)`.
+    ///
+    /// The original vertices keep their handles and the new vertices are added after them,
+    /// ordered like the edges they replace, so the result has `n + m` vertices and `2m` edges.
+    pub fn subdivide(&self, make_vertex: impl Fn(&V, &V) -> V) -> Unweighted<V> {
+        let mut subdivided: Unweighted<V> = self.vertices.iter().cloned().collect();
+        for (from, edges) in self.edges.iter().enumerate() {
+            for &to in edges {
+                let middle =
+                    subdivided.add_vertex(make_vertex(&self.vertices[from], &self.vertices[to.0]));
+                subdivided.add_edge(Handle(from), middle);
+                subdivided.add_edge(middle, to);
+            }
         }
+        subdivided
+    }
+}
 
-        for (from, edge) in self.edges.iter().enumerate() {
-            let from = &self.vertices[from];
-            let from = format!("{from:?}");
-            let from = make_safer(&from);
+impl<V> Unweighted<V>
+where
+    V: Eq,
+{
+    /// Returns whether there is an edge from the first vertex containing `from`
+    /// to the first vertex containing `to`. Returns `false` if either value is not in the graph.
+    ///
+    /// ```
+    /// use comtesse::unweighted::Unweighted;
+    ///
+    /// let mut graph: Unweighted<_> = (1..=10).collect();
+    /// graph.construct_edges_from(|&u, &v| u != v && (u + v) % 10 == 0);
+    ///
+    /// assert!(graph.has_edge_between_values(&1, &9));
+    /// assert!(!graph.has_edge_between_values(&1, &8));
+    /// assert!(!graph.has_edge_between_values(&1, &11));
+    /// ```
+    pub fn has_edge_between_values(&self, from: &V, to: &V) -> bool {
+        let position = |value: &V| self.vertices.iter().position(|vertex| vertex == value);
+        match (position(from), position(to)) {
+            (Some(from), Some(to)) => self.edges[from].contains(&Handle(to)),
+            _ => false,
+        }
+    }
+}
 
-            for &to in edge {
-                let to = &self.vertices[to.0];
-                let to = format!("{to:?}");
-                let to = make_safer(&to);
+impl<V> Unweighted<V>
+where
+    V: Eq + Hash,
+{
+    /// Builds a graph from a list of edges given as pairs of vertex values.
+    ///
+    /// Every distinct value becomes one vertex, in the order of its first appearance,
+    /// and every pair becomes one edge, so repeated pairs result in parallel edges.
+    ///
+    /// ```
+    /// # use comtesse::unweighted::Unweighted;
+    /// let graph = Unweighted::from_edges([("a", "b"), ("b", "c"), ("a", "c")]);
+    ///
+    /// assert_eq!(graph.size(), 3);
+    /// assert!(graph.has_edge_between_values(&"a", &"c"));
+    /// ```
+    pub fn from_edges(edges: impl IntoIterator<Item = (V, V)>) -> Unweighted<V> {
+        let mut graph = Unweighted::new();
+        // vertices are bucketed by their hash, since the values themselves are moved into the graph
+        let hasher = RandomState::new();
+        let mut buckets: HashMap<u64, Vec<usize>> = HashMap::new();
 
-                writeln!(output, "  \"{from}\" -> \"{to}\";\n")?;
+        let mut handle_of = |graph: &mut Unweighted<V>, value: V| {
+            let bucket = buckets.entry(hasher.hash_one(&value)).or_default();
+            match bucket.iter().find(|&&i| graph.vertices[i] == value) {
+                Some(&i) => Handle(i),
+                None => {
+                    let handle = graph.add_vertex(value);
+                    bucket.push(handle.0);
+                    handle
+                }
             }
+        };
+
+        for (from, to) in edges {
+            let from = handle_of(&mut graph, from);
+            let to = handle_of(&mut graph, to);
+            graph.add_edge(from, to);
         }
-        writeln!(output, "}}")?;
+        graph
+    }
+}
 
-        Ok(())
+impl<V: Debug> DumpGraphviz for Unweighted<V> {
+    fn dump(&self, output: &mut dyn Write) -> Result<(), std::fmt::Error> {
+        self.write_dot(output, |_, _| String::new())
+    }
+}
+
+impl<V: Debug> Unweighted<V> {
+    /// Returns the graph in the DOT format with the vertices and edges sorted by their labels,
+    /// so that the output does not depend on the order in which vertices and edges were added
+    ///
+    /// ```
+    /// # use comtesse::unweighted::Unweighted;
+    /// let mut graph: Unweighted<_> = [2, 1].into_iter().collect();
+    /// graph.construct_edges_from(|&u, &v| u != v);
+    ///
+    /// assert_eq!(
+    ///     graph.to_dot_sorted(),
+    ///     r#"digraph {
+    ///   "1";
+    ///   "2";
+    ///   "1" -> "2";
+    ///   "2" -> "1";
+    /// }
+    /// "#
+    /// );
+    /// ```
+    pub fn to_dot_sorted(&self) -> String {
+        self.to_dot_sorted_with(|_, _| String::new())
+    }
+}
+
+impl<V: Display> Unweighted<V> {
+    /// Returns the graph in the GraphML format, using the values of the vertices as their labels
+    ///
+    /// ```
+    /// # use comtesse::unweighted::Unweighted;
+    /// let mut graph: Unweighted<_> = (1..=3).collect();
+    /// graph.construct_edges_from(|&u, &v| v == u + 1);
+    ///
+    /// let graphml = graph.to_graphml();
+    /// assert!(graphml.contains(r#"<edge source="n0" target="n1"/>"#));
+    /// ```
+    pub fn to_graphml(&self) -> String {
+        let mut output = String::new();
+        self.dump_graphml(&mut output)
+            .expect("writing to a String does not fail");
+        output
+    }
+
+    fn dump_graphml(&self, output: &mut dyn Write) -> std::fmt::Result {
+        self.write_graphml(output, None)
     }
 }
 
@@ -133,6 +560,14 @@ impl<V> HasEdge for Unweighted<V> {
         let vertex = vertex.0;
         Box::new(self.edges[vertex].iter().copied())
     }
+
+    fn neighbors_snapshot(&self, vertex: Handle) -> Vec<Handle> {
+        self.edges[vertex.0].clone()
+    }
+
+    fn neighbor_count(&self, vertex: Handle) -> usize {
+        self.edges[vertex.0].len()
+    }
 }
 
 impl<V, W> From<Weighted<V, W>> for Unweighted<V>
@@ -183,3 +618,266 @@ fn from_weighted() {
         graph.get_vertex('c').unwrap()
     ));
 }
+
+#[test]
+fn degrees() {
+    let mut cycle: Unweighted<_> = (0..6).collect();
+    cycle.construct_edges_from(|&u, &v| v == (u + 1) % 6);
+    assert!(cycle.is_regular());
+    assert_eq!(cycle.max_degree(), Some(2));
+    assert_eq!(cycle.min_degree(), Some(2));
+
+    let mut star: Unweighted<_> = (0..6).collect();
+    star.construct_edges_from(|&u, &v| u == 0 && v != 0);
+    let center = star.get_vertex(0).unwrap();
+    assert!(!star.is_regular());
+    assert_eq!(star.max_degree(), Some(5));
+    assert_eq!(star.min_degree(), Some(1));
+    assert_eq!(star.out_degree(center), 5);
+    assert_eq!(star.in_degree(center), 0);
+
+    star.add_edge(center, center);
+    assert_eq!(star.degree(center), 7);
+
+    assert!(Unweighted::<u8>::new().is_regular());
+    assert_eq!(Unweighted::<u8>::new().max_degree(), None);
+}
+
+#[test]
+fn edge_difference() {
+    let mut old: Unweighted<_> = (1..=6).collect();
+    old.construct_edges_from(|&u, &v| u != v && v % u == 0);
+    let mut new: Unweighted<_> = (1..=6).collect();
+    new.construct_edges_from(|&u, &v| u != v && v % u == 0);
+
+    let two = new.get_vertex(2).unwrap();
+    let three = new.get_vertex(3).unwrap();
+    let five = new.get_vertex(5).unwrap();
+    new.add_edge(five, three);
+    new.add_edge(five, three);
+    new.remove_edge(two, new.get_vertex(4).unwrap());
+
+    assert_eq!(new.edge_difference(&old), [(five, three)]);
+    assert_eq!(
+        old.edge_difference(&new),
+        [(two, old.get_vertex(4).unwrap())]
+    );
+    assert!(old.edge_difference(&old).is_empty());
+}
+
+#[test]
+fn construct_checked() {
+    let mut graph: Unweighted<_> = (1..=10).collect();
+    let report = graph.construct_edges_from_checked(|&u, &v| u != v && (u + v) % 10 == 0);
+    // (1, 9), (2, 8), (3, 7), (4, 6) in both directions
+    assert_eq!(
+        report,
+        ConstructionReport {
+            edges: 8,
+            self_loops: 0,
+            symmetric_pairs: 4,
+        }
+    );
+    assert_eq!(graph.num_edges(), 8);
+
+    let mut graph: Unweighted<_> = (1..=10).collect();
+    let report = graph.construct_edges_from_checked(|&u, &v| v % u == 0);
+    assert_eq!(report.symmetric_pairs, 0);
+    assert_eq!(report.self_loops, 10);
+    assert_eq!(report.edges, graph.num_edges());
+}
+
+#[test]
+fn contract_matching() {
+    let mut cycle: Unweighted<_> = ('a'..='d').collect();
+    cycle.construct_edges_from(|&u, &v| {
+        matches!(
+            (u.min(v), u.max(v)),
+            ('a', 'b') | ('b', 'c') | ('c', 'd') | ('a', 'd')
+        )
+    });
+    let handles: Vec<_> = ('a'..='d').map(|v| cycle.get_vertex(v).unwrap()).collect();
+
+    assert_eq!(
+        cycle.contract_matching(&[(handles[0], handles[1]), (handles[1], handles[2])]),
+        Err(MatchingError::SharedVertex(handles[1]))
+    );
+    assert_eq!(
+        cycle.contract_matching(&[(handles[0], handles[1]), (handles[1], handles[1])]),
+        Err(MatchingError::NotAnEdge(handles[1], handles[1]))
+    );
+    assert_eq!(
+        cycle.contract_matching(&[(handles[3], handles[2]), (handles[0], handles[2])]),
+        Err(MatchingError::NotAnEdge(handles[0], handles[2]))
+    );
+    assert_eq!(cycle.size(), 4);
+
+    let remap = cycle
+        .contract_matching(&[(handles[0], handles[1]), (handles[3], handles[2])])
+        .unwrap();
+    assert_eq!(cycle.size(), 2);
+    let remapped: Vec<_> = handles.iter().map(|&old| remap.map(old)).collect();
+    assert_eq!(
+        remapped,
+        [
+            Some(Handle(0)),
+            Some(Handle(0)),
+            Some(Handle(1)),
+            Some(Handle(1))
+        ]
+    );
+    assert_eq!(*cycle.vertex_value(remap.map(handles[0]).unwrap()), 'a');
+    assert_eq!(*cycle.vertex_value(remap.map(handles[2]).unwrap()), 'd');
+
+    // b -> c and a -> d both become edges between the merged vertices
+    assert_eq!(cycle.num_edges(), 4);
+    assert_eq!(cycle.neighbors(Handle(0)), [Handle(1), Handle(1)]);
+    assert_eq!(cycle.neighbors(Handle(1)), [Handle(0), Handle(0)]);
+}
+
+#[test]
+fn graphml() {
+    let mut graph: Unweighted<_> = ["<a>", "b & c", "\"d\""].into_iter().collect();
+    graph.construct_edges_from(|&u, &v| u != v);
+
+    let graphml = graph.to_graphml();
+    assert!(graphml.starts_with("<?xml"));
+    assert_eq!(graphml.matches("<node ").count(), 3);
+    assert_eq!(graphml.matches("<edge ").count(), 6);
+    assert_eq!(graphml.matches("<graphml").count(), 1);
+    assert_eq!(graphml.matches("</graphml>").count(), 1);
+    assert_eq!(graphml.matches("<graph ").count(), 1);
+    assert_eq!(graphml.matches("</graph>").count(), 1);
+    assert!(graphml.contains("&lt;a&gt;"));
+    assert!(graphml.contains("b &amp; c"));
+    assert!(graphml.contains("&quot;d&quot;"));
+    assert!(!graphml.contains("<a>"));
+}
+
+#[test]
+fn retain_edges() {
+    let mut graph: Unweighted<_> = (0..5).collect();
+    graph.construct_edges_from(|&u, &v| u != v);
+
+    graph.retain_edges(|from, to| from < to);
+    assert_eq!(graph.num_edges(), 10);
+    assert!(graph.edges_with_values().all(|(&u, &v)| u < v));
+}
+
+#[test]
+fn neighbor_count() {
+    let mut graph: Unweighted<_> = (1..=12).collect();
+    graph.construct_edges_from(|&u, &v| v % u == 0);
+    graph.add_edge(Handle(0), Handle(1));
+
+    for vertex in (0..graph.size()).map(Handle) {
+        assert_eq!(graph.neighbor_count(vertex), graph.out_degree(vertex));
+        assert_eq!(
+            graph.neighbor_count(vertex),
+            graph.connected_neighbors(vertex).count()
+        );
+    }
+}
+
+#[test]
+fn dot_sorted() {
+    let mut forward: Unweighted<_> = ('a'..='e').collect();
+    forward.construct_edges_from(|&u, &v| u < v);
+
+    let mut backward: Unweighted<_> = ('a'..='e').rev().collect();
+    for i in (0..5).rev() {
+        for j in 0..5 {
+            if backward.vertices[i] < backward.vertices[j] {
+                backward.add_edge(Handle(i), Handle(j));
+            }
+        }
+    }
+
+    assert_ne!(forward.neighbors(Handle(0)), backward.neighbors(Handle(4)));
+    assert_eq!(forward.to_dot_sorted(), backward.to_dot_sorted());
+}
+
+#[test]
+fn from_edges() {
+    let triangle = Unweighted::from_edges([(1, 2), (2, 3), (3, 1)]);
+    assert_eq!(triangle.size(), 3);
+    assert_eq!(triangle.num_edges(), 3);
+    assert_eq!(triangle.vertices, [1, 2, 3]);
+    assert!(triangle.has_edge_between_values(&3, &1));
+    assert!(!triangle.has_edge_between_values(&1, &3));
+
+    let repeated = Unweighted::from_edges([("x", "y"), ("x", "y"), ("y", "y")]);
+    assert_eq!(repeated.size(), 2);
+    assert_eq!(repeated.num_edges(), 3);
+}
+
+#[test]
+fn add_edges() {
+    let mut graph: Unweighted<_> = (0..3).collect();
+
+    let invalid = [
+        (Handle(0), Handle(1)),
+        (Handle(1), Handle(7)),
+        (Handle(9), Handle(0)),
+    ];
+    assert_eq!(graph.add_edges(&invalid), Err(Handle(7)));
+    assert_eq!(graph.num_edges(), 0);
+
+    assert_eq!(
+        graph.add_edges(&[(Handle(0), Handle(1)), (Handle(1), Handle(2))]),
+        Ok(())
+    );
+    assert_eq!(graph.num_edges(), 2);
+    assert!(graph.has_edge(Handle(1), Handle(2)));
+}
+
+#[test]
+fn subdivide() {
+    let mut triangle: Unweighted<_> = (0..3).map(|v| v * 10).collect();
+    triangle.construct_edges_from(|&u, &v| v == (u + 10) % 30);
+
+    let subdivided = triangle.subdivide(|&u, &v| (u + v) / 2);
+    assert_eq!(subdivided.size(), 6);
+    assert_eq!(subdivided.num_edges(), 6);
+    assert_eq!(subdivided.vertices, [0, 10, 20, 5, 15, 10]);
+    assert_eq!(subdivided.neighbors(Handle(0)), [Handle(3)]);
+    assert_eq!(subdivided.neighbors(Handle(3)), [Handle(1)]);
+    assert_eq!(subdivided.neighbors(Handle(5)), [Handle(0)]);
+}
+
+#[test]
+fn line_graph() {
+    let mut path: Unweighted<_> = (0..3).collect();
+    path.construct_edges_from(|&u, &v| v == u + 1);
+
+    let line = path.line_graph();
+    assert_eq!(line.size(), 2);
+    assert_eq!(line.num_edges(), 1);
+    assert_eq!(
+        line.vertices,
+        [(Handle(0), Handle(1)), (Handle(1), Handle(2))]
+    );
+    assert!(line.has_edge(Handle(0), Handle(1)));
+
+    let mut star: Unweighted<_> = (0..4).collect();
+    star.construct_edges_from(|&u, &v| (u == 0) != (v == 0));
+    let line = star.line_graph();
+    assert_eq!(line.size(), 6);
+    // every edge into the center continues along any of the three edges out of it
+    assert_eq!(line.num_edges(), 3 * 3 + 3);
+}
+
+#[test]
+fn density() {
+    let mut graph: Unweighted<_> = (0..5).collect();
+    assert_eq!(graph.density(), 0.0);
+
+    graph.construct_edges_from(|&u, &v| u < v);
+    assert_eq!(graph.density(), 0.5);
+
+    graph.construct_edges_from(|&u, &v| u > v);
+    assert_eq!(graph.density(), 1.0);
+
+    let single: Unweighted<_> = (0..1).collect();
+    assert_eq!(single.density(), 0.0);
+}
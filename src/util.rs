@@ -0,0 +1,389 @@
+//! utilities used to implement graph algorithms
+
+use std::collections::VecDeque;
+
+use crate::{
+    graph::{Graph, Handle},
+    HasEdge,
+};
+
+/// Union-find over the elements `0..n`, with path compression and union by rank
+///
+/// ```
+/// use comtesse::util::DisjointSet;
+///
+/// let mut sets = DisjointSet::new(4);
+/// sets.union(0, 1);
+/// sets.union(2, 3);
+/// assert!(sets.same_set(1, 0));
+/// assert!(!sets.same_set(1, 2));
+/// ```
+#[derive(Clone, Debug)]
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+}
+
+impl DisjointSet {
+    /// Constructs `n` singleton sets
+    pub fn new(n: usize) -> Self {
+        DisjointSet {
+            parent: (0..n).collect(),
+            rank: vec![0; n],
+        }
+    }
+
+    /// Returns the representative of the set containing `element`.
+    /// Two elements are in the same set if and only if they have the same representative
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `element` is not smaller than the number of elements
+    pub fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        let mut cur = element;
+        while self.parent[cur] != root {
+            let next = self.parent[cur];
+            self.parent[cur] = root;
+            cur = next;
+        }
+
+        root
+    }
+
+    /// Merges the sets containing `a` and `b`, returning `false` if they already were the same set
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        match self.rank[a].cmp(&self.rank[b]) {
+            std::cmp::Ordering::Less => self.parent[a] = b,
+            std::cmp::Ordering::Greater => self.parent[b] = a,
+            std::cmp::Ordering::Equal => {
+                self.parent[b] = a;
+                self.rank[a] += 1;
+            }
+        }
+        true
+    }
+
+    /// Returns whether `a` and `b` are in the same set
+    pub fn same_set(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+}
+
+/// Tracks which vertices are connected while edges are added one at a time, ignoring their direction.
+///
+/// Queries take near-constant amortized time, which is much cheaper than running
+/// [Graph::is_connected] or [Graph::connected_components] after every change.
+/// Edges can not be removed again, as the underlying [DisjointSet] can not split sets.
+///
+/// ```
+/// # use comtesse::{unweighted::Unweighted, util::DynamicConnectivity};
+/// let graph: Unweighted<_> = (0..3).collect();
+/// let [a, b, c] = [0, 1, 2].map(|i| graph.get_vertex(i).unwrap());
+///
+/// let mut connectivity = DynamicConnectivity::from_graph(&graph);
+/// connectivity.add_edge(a, b);
+/// assert!(connectivity.connected(b, a));
+/// assert!(!connectivity.connected(a, c));
+/// ```
+#[derive(Clone, Debug)]
+pub struct DynamicConnectivity {
+    sets: DisjointSet,
+    components: usize,
+}
+
+impl DynamicConnectivity {
+    /// Constructs the connectivity of `size` vertices without any edges
+    pub fn new(size: usize) -> Self {
+        DynamicConnectivity {
+            sets: DisjointSet::new(size),
+            components: size,
+        }
+    }
+
+    /// Constructs the connectivity of the vertices of `graph` including all of its current edges.
+    /// Edges added to `graph` later on are not tracked
+    pub fn from_graph<V, E>(graph: &Graph<V, E>) -> Self
+    where
+        Graph<V, E>: HasEdge,
+    {
+        let mut connectivity = Self::new(graph.size());
+        for from in (0..graph.size()).map(Handle) {
+            for to in graph.connected_neighbors(from) {
+                connectivity.add_edge(from, to);
+            }
+        }
+        connectivity
+    }
+
+    /// Adds an edge between `a` and `b`, returning `false` if they already were connected
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `a` or `b` is not one of the tracked vertices
+    pub fn add_edge(&mut self, a: Handle, b: Handle) -> bool {
+        let merged = self.sets.union(a.0, b.0);
+        if merged {
+            self.components -= 1;
+        }
+        merged
+    }
+
+    /// Returns whether `a` and `b` are connected by the edges added so far
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `a` or `b` is not one of the tracked vertices
+    pub fn connected(&mut self, a: Handle, b: Handle) -> bool {
+        self.sets.same_set(a.0, b.0)
+    }
+
+    /// Returns the number of connected components
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+}
+
+/// Reusable buffers for breadth-first and depth-first searches.
+///
+/// [Graph::bfs_distances] and [Graph::dfs_order] allocate fresh buffers on every call.
+/// When running many traversals, a `Traversal` keeps its queue, stack and visited flags between calls,
+/// so after the first few searches no further allocations are needed.
+///
+/// ```
+/// # use comtesse::{unweighted::Unweighted, util::Traversal};
+/// let mut graph: Unweighted<_> = (0..4).collect();
+/// graph.construct_edges_from(|&u, &v| v == u + 1);
+///
+/// let mut traversal = Traversal::new();
+/// let mut out = vec![];
+/// for start in 0..4 {
+///     let start = graph.get_vertex(start).unwrap();
+///     traversal.bfs_into(&graph, start, &mut out);
+///     assert_eq!(out.first(), Some(&start));
+/// }
+/// assert_eq!(out.len(), 1);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Traversal {
+    visited: Vec<bool>,
+    queue: VecDeque<usize>,
+    stack: Vec<usize>,
+}
+
+impl Traversal {
+    /// Constructs a traversal with empty buffers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the contents of `out` with the vertices reachable from `start`,
+    /// in the order in which a breadth-first search visits them.
+    /// Neighbors are visited in the order of [HasEdge::connected_neighbors].
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `start` is not a vertex of `graph`
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(k + m)` where `k` is the number of reachable vertices
+    /// and `m` is the number of their outgoing edges
+    pub fn bfs_into<V, E>(&mut self, graph: &Graph<V, E>, start: Handle, out: &mut Vec<Handle>)
+    where
+        Graph<V, E>: HasEdge,
+    {
+        self.prepare(graph, start, out);
+        self.visited[start.0] = true;
+        self.queue.push_back(start.0);
+
+        while let Some(front) = self.queue.pop_front() {
+            out.push(Handle(front));
+            for Handle(neighbor) in graph.connected_neighbors(Handle(front)) {
+                if !self.visited[neighbor] {
+                    self.visited[neighbor] = true;
+                    self.queue.push_back(neighbor);
+                }
+            }
+        }
+
+        self.reset(out);
+    }
+
+    /// Replaces the contents of `out` with the vertices reachable from `start`,
+    /// in the same order as [Graph::dfs_order] with [DfsOrder::PreOrder](crate::DfsOrder::PreOrder).
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `start` is not a vertex of `graph`
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(k + m)` where `k` is the number of reachable vertices
+    /// and `m` is the number of their outgoing edges
+    pub fn dfs_into<V, E>(&mut self, graph: &Graph<V, E>, start: Handle, out: &mut Vec<Handle>)
+    where
+        Graph<V, E>: HasEdge,
+    {
+        self.prepare(graph, start, out);
+        self.stack.push(start.0);
+
+        while let Some(top) = self.stack.pop() {
+            if self.visited[top] {
+                continue;
+            }
+            self.visited[top] = true;
+            out.push(Handle(top));
+
+            // push in reverse so the first neighbor is visited first, as in a recursive search
+            let pushed = self.stack.len();
+            self.stack.extend(
+                graph
+                    .connected_neighbors(Handle(top))
+                    .map(|Handle(neighbor)| neighbor)
+                    .filter(|&neighbor| !self.visited[neighbor]),
+            );
+            self.stack[pushed..].reverse();
+        }
+
+        self.reset(out);
+    }
+
+    /// Clears `out` and sizes the visited flags for `graph`
+    fn prepare<V, E>(&mut self, graph: &Graph<V, E>, start: Handle, out: &mut Vec<Handle>) {
+        assert!(graph.is_valid_handle(start), "invalid start vertex");
+        out.clear();
+        self.visited.resize(graph.size(), false);
+    }
+
+    /// Clears the visited flags set during a search, touching only the visited vertices
+    fn reset(&mut self, out: &[Handle]) {
+        for &Handle(vertex) in out {
+            self.visited[vertex] = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::{DisjointSet, DynamicConnectivity, Traversal};
+    use crate::{graph::Handle, unweighted::Unweighted, DfsOrder};
+
+    #[test]
+    fn singletons() {
+        let mut sets = DisjointSet::new(5);
+        for i in 0..5 {
+            assert_eq!(sets.find(i), i);
+            for j in 0..5 {
+                assert_eq!(sets.same_set(i, j), i == j);
+            }
+        }
+    }
+
+    #[test]
+    fn union() {
+        let mut sets = DisjointSet::new(6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+
+        assert!(sets.same_set(0, 3));
+        assert_eq!(sets.find(0), sets.find(2));
+        assert!(!sets.same_set(0, 4));
+        assert!(!sets.same_set(4, 5));
+
+        assert!(sets.union(4, 5));
+        assert!(sets.same_set(5, 4));
+        assert!(!sets.same_set(5, 1));
+    }
+
+    #[test]
+    fn long_chain() {
+        let mut sets = DisjointSet::new(1000);
+        for i in 1..1000 {
+            sets.union(i - 1, i);
+        }
+        let root = sets.find(0);
+        assert!((0..1000).all(|i| sets.find(i) == root));
+    }
+
+    #[test]
+    fn traversal_reuse() {
+        let mut graph: Unweighted<_> = (0..12).collect();
+        graph.construct_edges_from(|&u, &v| {
+            u != v && (v == 2 * u || v == u + 3 || (u == 11 && v == 0))
+        });
+
+        let reference_bfs = |start: Handle| {
+            let mut visited = vec![false; graph.size()];
+            let mut queue = VecDeque::from([start]);
+            let mut order = vec![];
+            visited[start.index()] = true;
+            while let Some(front) = queue.pop_front() {
+                order.push(front);
+                for &neighbor in graph.neighbors(front) {
+                    if !visited[neighbor.index()] {
+                        visited[neighbor.index()] = true;
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+            order
+        };
+
+        let mut traversal = Traversal::new();
+        let mut out = vec![Handle(5)];
+        for _ in 0..2 {
+            for start in (0..12).map(Handle) {
+                traversal.dfs_into(&graph, start, &mut out);
+                assert_eq!(out, graph.dfs_order(start, DfsOrder::PreOrder));
+
+                traversal.bfs_into(&graph, start, &mut out);
+                assert_eq!(out, reference_bfs(start));
+            }
+
+            traversal.bfs_into(&graph, Handle(1), &mut out);
+            let expected = [1, 2, 4, 5, 7, 8, 10, 11, 0, 3, 6, 9].map(Handle);
+            assert_eq!(out, expected);
+        }
+
+        let smaller: Unweighted<_> = (0..3).collect();
+        traversal.bfs_into(&smaller, Handle(2), &mut out);
+        assert_eq!(out, [Handle(2)]);
+    }
+
+    #[test]
+    fn dynamic_connectivity() {
+        let mut graph: Unweighted<_> = (0..6).collect();
+        graph.add_edge(Handle(0), Handle(1));
+
+        let mut connectivity = DynamicConnectivity::from_graph(&graph);
+        assert_eq!(connectivity.component_count(), 5);
+        assert!(connectivity.connected(Handle(1), Handle(0)));
+        assert!(!connectivity.connected(Handle(1), Handle(2)));
+
+        assert!(connectivity.add_edge(Handle(2), Handle(1)));
+        assert!(connectivity.connected(Handle(0), Handle(2)));
+        assert!(!connectivity.connected(Handle(0), Handle(3)));
+
+        assert!(connectivity.add_edge(Handle(3), Handle(4)));
+        assert!(!connectivity.add_edge(Handle(4), Handle(3)));
+        assert!(!connectivity.connected(Handle(2), Handle(4)));
+        assert_eq!(connectivity.component_count(), 3);
+
+        assert!(connectivity.add_edge(Handle(4), Handle(0)));
+        assert!(connectivity.connected(Handle(2), Handle(3)));
+        assert!(!connectivity.connected(Handle(5), Handle(3)));
+        assert_eq!(connectivity.component_count(), 2);
+    }
+}
@@ -1,10 +1,11 @@
 //! A weighted Graph, containing vertices of type `V`
 
-use std::fmt::Write;
+use std::{cmp::Ordering, collections::HashMap, fmt::Write, hash::Hash};
 
 use crate::{
-    graph::{Graph, Handle},
-    make_safer, DumpGraphviz, HasEdge,
+    graph::{Edge, Graph, Handle, HandleRemap},
+    unweighted::Unweighted,
+    DumpGraphviz, HasEdge, SizeMismatch,
 };
 
 /// A Connection between two vertices, also called 'Edge'.
@@ -35,6 +36,19 @@ where
     }
 }
 
+impl<W> Edge for Connection<W>
+where
+    W: num_traits::Num + Copy,
+{
+    fn pointing_to(&self) -> Handle {
+        self.to
+    }
+
+    fn redirect(&mut self, to: Handle) {
+        self.to = to;
+    }
+}
+
 /// A weighted Graph, containing vertices of type `V`. A connection in this Graph is represented by [Connection]
 pub type Weighted<V, W> = Graph<V, Connection<W>>;
 
@@ -52,6 +66,9 @@ where
     ///
     /// the condition function should return `None` if the condition is not met
     /// and `Some(weight)` if the condition is met
+    ///
+    /// The condition is called for every ordered pair of vertices, so `(u, v)` and `(v, u)` may get
+    /// different weights. Use [Weighted::construct_undirected_edges_from] to get the same weight in both directions.
     pub fn construct_edges_from<F>(&mut self, condition: F)
     where
         F: Fn(&V, &V) -> Option<W>,
@@ -65,6 +82,53 @@ where
         }
     }
 
+    /// Constructs undirected edges that satisfy the given `condition`.
+    ///
+    /// Unlike [Weighted::construct_edges_from], the condition is called only once for every unordered pair
+    /// of vertices `u` and `v` (with `u` added before `v`) and a returned weight is used for an edge in both directions.
+    /// Self loops are only added once.
+    pub fn construct_undirected_edges_from<F>(&mut self, condition: F)
+    where
+        F: Fn(&V, &V) -> Option<W>,
+    {
+        for u in 0..self.vertices.len() {
+            for v in u..self.vertices.len() {
+                if let Some(weight) = condition(&self.vertices[u], &self.vertices[v]) {
+                    self.add_edge(Handle(u), Handle(v), weight);
+                    if u != v {
+                        self.add_edge(Handle(v), Handle(u), weight);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Constructs edges that satisfy the given `condition`, only comparing vertices with equal `key`.
+    ///
+    /// This produces the same edges in the same order as [Weighted::construct_edges_from]
+    /// as long as `condition` returns `None` for every pair of vertices with different keys,
+    /// but only calls `condition` for pairs of vertices within the same bucket.
+    pub fn construct_edges_by_key<K, F, G>(&mut self, key: G, condition: F)
+    where
+        K: Eq + Hash,
+        G: Fn(&V) -> K,
+        F: Fn(&V, &V) -> Option<W>,
+    {
+        let keys: Vec<K> = self.vertices.iter().map(key).collect();
+        let mut buckets: HashMap<&K, Vec<usize>> = HashMap::new();
+        for (vertex, key) in keys.iter().enumerate() {
+            buckets.entry(key).or_default().push(vertex);
+        }
+
+        for (u, key) in keys.iter().enumerate() {
+            for &v in &buckets[key] {
+                if let Some(weight) = condition(&self.vertices[u], &self.vertices[v]) {
+                    self.add_edge(Handle(u), Handle(v), weight)
+                }
+            }
+        }
+    }
+
     /// Returns `Some(weight)` if the edge exists where `weight` is the weight of the found edge.
     /// Otherwise returns None
     pub fn get_edge(&self, from: Handle, to: Handle) -> Option<W> {
@@ -75,6 +139,23 @@ where
             .map(|Connection { weight, .. }| *weight)
     }
 
+    /// Returns a reference to the weight of the edge from `from` to `to` if it exists.
+    /// Unlike [Weighted::get_edge], this does not copy the weight
+    pub fn get_edge_ref(&self, from: Handle, to: Handle) -> Option<&W> {
+        self.edges[from.0]
+            .iter()
+            .find(|connection| connection.to == to)
+            .map(|connection| &connection.weight)
+    }
+
+    /// Returns the connection from `from` to `to` if it exists.
+    /// If there are multiple edges between the two vertices, the first one added is returned
+    pub fn get_connection(&self, from: Handle, to: Handle) -> Option<&Connection<W>> {
+        self.edges[from.0]
+            .iter()
+            .find(|connection| connection.to == to)
+    }
+
     /// Removes the edge going from `from` to `to`.
     ///
     /// ## Panics
@@ -94,11 +175,375 @@ where
         self.edges[from.0].swap_remove(to);
     }
 
+    /// Removes every edge for which `keep(from, to, &weight)` returns `false`
+    ///
+    /// ```
+    /// # use comtesse::weighted::Weighted;
+    /// let mut graph: Weighted<_, u32> = (1..=5).collect();
+    /// graph.construct_edges_from(|&u, &v| (u != v).then_some(u + v));
+    ///
+    /// // only keep the heavy edges
+    /// graph.retain_edges(|_, _, &weight| weight > 6);
+    /// assert_eq!(graph.num_edges(), 8);
+    /// ```
+    pub fn retain_edges(&mut self, keep: impl Fn(Handle, Handle, &W) -> bool) {
+        for (from, edges) in self.edges.iter_mut().enumerate() {
+            edges.retain(|Connection { to, weight }| keep(Handle(from), *to, weight));
+        }
+    }
+
+    /// Contracts the edge between `a` and `b`, merging `b` into `a`.
+    ///
+    /// The merged vertex keeps the value of `a`. The edges between `a` and `b` and the self loops of `b` are removed,
+    /// all other edges of `b` become edges of `a`. If `a` and `b` both had an edge to (or from) the same vertex,
+    /// only one edge remains, whose weight is `combine(weight of a's edge, weight of b's edge)`.
+    ///
+    /// Since `b` is removed, the handles of the vertices after it change. The returned [HandleRemap] maps the old
+    /// handles to the new ones, with `b` being mapped to the merged vertex.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `a` and `b` are the same vertex
+    pub fn contract_edge(
+        &mut self,
+        a: Handle,
+        b: Handle,
+        combine: impl Fn(W, W) -> W,
+    ) -> HandleRemap {
+        assert_ne!(a, b, "can not contract a vertex with itself");
+
+        for Connection { to, weight } in std::mem::take(&mut self.edges[b.0]) {
+            if to == a || to == b {
+                continue;
+            }
+            match self.edges[a.0]
+                .iter_mut()
+                .find(|connection| connection.to == to)
+            {
+                Some(connection) => connection.weight = combine(connection.weight, weight),
+                None => self.edges[a.0].push(Connection { to, weight }),
+            }
+        }
+
+        for from in 0..self.size() {
+            if from == a.0 {
+                self.edges[from].retain(|connection| connection.to != b);
+                continue;
+            }
+            while let Some(position) = self.edges[from].iter().position(|c| c.to == b) {
+                let weight = self.edges[from].swap_remove(position).weight;
+                match self.edges[from]
+                    .iter_mut()
+                    .find(|connection| connection.to == a)
+                {
+                    Some(connection) => connection.weight = combine(connection.weight, weight),
+                    None => self.edges[from].push(Connection { to: a, weight }),
+                }
+            }
+        }
+
+        let mut keep = vec![true; self.size()];
+        keep[b.0] = false;
+        let mut remap = self.retain_by_handle(&keep);
+        remap.0[b.0] = remap.0[a.0];
+        remap
+    }
+
+    /// Collapses every group of vertices into a single vertex, where `partition[v]` is the group of the vertex with handle `v`.
+    ///
+    /// The returned graph has one vertex for every group that occurs in `partition`, holding the group
+    /// and ordered by it, so the group ids do not have to be consecutive. All edges from group `g` to a different group `h`
+    /// are replaced by a single edge from `g` to `h` whose weight is the sum of their weights,
+    /// while edges within a group are dropped.
+    ///
+    /// Returns [SizeMismatch] if the length of `partition` differs from the number of vertices.
+    ///
+    /// ## Running Time
+    /// This algorithm has a running time of `O(n log n + m)` where `n` is the number of vertices and `m` is the number of edges
+    pub fn quotient_graph(&self, partition: &[usize]) -> Result<Weighted<usize, W>, SizeMismatch> {
+        if partition.len() != self.size() {
+            return Err(SizeMismatch {
+                expected: self.size(),
+                found: partition.len(),
+            });
+        }
+        let mut groups = partition.to_vec();
+        groups.sort_unstable();
+        groups.dedup();
+        let partition: Vec<_> = partition
+            .iter()
+            .map(|group| {
+                groups
+                    .binary_search(group)
+                    .expect("every group was collected")
+            })
+            .collect();
+        let mut quotient: Weighted<usize, W> = groups.into_iter().collect();
+
+        // position of the edge between two groups in the adjacency list of the first one
+        let mut positions: HashMap<(usize, usize), usize> = HashMap::new();
+        for (from, edges) in self.edges.iter().enumerate() {
+            let from = partition[from];
+            for &Connection { to, weight } in edges {
+                let to = partition[to.0];
+                if from == to {
+                    continue;
+                }
+                let connections = &mut quotient.edges[from];
+                match positions.get(&(from, to)) {
+                    Some(&position) => {
+                        let connection = &mut connections[position];
+                        connection.weight = connection.weight + weight;
+                    }
+                    None => {
+                        positions.insert((from, to), connections.len());
+                        connections.push(Connection {
+                            to: Handle(to),
+                            weight,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(quotient)
+    }
+
     /// returns a list of neighbors of `vertex` in the graph
     pub fn neighbors(&self, vertex: Handle) -> &[Connection<W>] {
         let vertex = vertex.0;
         &self.edges[vertex]
     }
+
+    /// Returns an iterator over the handles of the neighbors of `vertex`, ignoring the weights of the edges
+    pub fn neighbor_handles(&self, vertex: Handle) -> impl Iterator<Item = Handle> + '_ {
+        self.edges[vertex.0].iter().map(|connection| connection.to)
+    }
+
+    /// Returns the sum of the weights of all edges.
+    ///
+    /// Undirected graphs that store every edge in both directions count every weight twice.
+    pub fn total_weight(&self) -> W {
+        self.edges
+            .iter()
+            .flatten()
+            .fold(W::zero(), |total, connection| total + connection.weight)
+    }
+
+    /// Splits the graph into its unweighted topology and the weights of its edges.
+    ///
+    /// The weights are aligned with the adjacency lists of the unweighted graph:
+    /// `weights[v][i]` is the weight of the edge to `unweighted.neighbors(v)[i]`.
+    /// Unlike converting via [From], this keeps edges of weight zero.
+    pub fn split_weights(self) -> (Unweighted<V>, Vec<Vec<W>>) {
+        let (edges, weights) = self
+            .edges
+            .into_iter()
+            .map(|connections| connections.into_iter().map(|c| (c.to, c.weight)).unzip())
+            .unzip();
+        (
+            Unweighted {
+                vertices: self.vertices,
+                edges,
+            },
+            weights,
+        )
+    }
+
+    /// Returns an iterator over all edges as the values of their endpoints together with their weight,
+    /// ordered by the handle of the starting vertex
+    ///
+    /// ```
+    /// # use comtesse::weighted::Weighted;
+    /// let mut graph: Weighted<_, u32> = (1..=10).collect();
+    /// graph.construct_edges_from(|&u, &v| (u != v && (u + v) % 10 == 0).then_some(u * v));
+    ///
+    /// for (u, v, weight) in graph.edges_with_values() {
+    ///     println!("{u} -> {v} ({weight})");
+    /// }
+    ///
+    /// let edges: Vec<_> = graph.edges_with_values().take(2).collect();
+    /// assert_eq!(edges, [(&1, &9, 9), (&2, &8, 16)]);
+    /// ```
+    pub fn edges_with_values(&self) -> impl Iterator<Item = (&V, &V, W)> {
+        self.edges
+            .iter()
+            .enumerate()
+            .flat_map(move |(from, edges)| {
+                edges.iter().map(move |connection| {
+                    (
+                        &self.vertices[from],
+                        &self.vertices[connection.to.0],
+                        connection.weight,
+                    )
+                })
+            })
+    }
+}
+
+impl<V, W> Weighted<V, W>
+where
+    V: Clone,
+    W: num_traits::Num + Copy,
+{
+    /// Returns the complement of the graph, which has an edge of weight `default_weight` from `u` to `v`
+    /// if and only if `u != v` and there is no edge from `u` to `v` in this graph.
+    ///
+    /// Self loops are never part of the complement and parallel edges count as a single edge.
+    /// The vertices of the complement have the same handles as in this graph.
+    pub fn complement_with(&self, default_weight: W) -> Weighted<V, W> {
+        let mut complement = Weighted {
+            vertices: self.vertices.clone(),
+            edges: Vec::with_capacity(self.size()),
+        };
+
+        let mut adjacent = vec![false; self.size()];
+        for (from, edges) in self.edges.iter().enumerate() {
+            adjacent.fill(false);
+            adjacent[from] = true;
+            for connection in edges {
+                adjacent[connection.to.0] = true;
+            }
+
+            complement.edges.push(
+                (0..self.size())
+                    .filter(|&to| !adjacent[to])
+                    .map(|to| Connection {
+                        to: Handle(to),
+                        weight: default_weight,
+                    })
+                    .collect(),
+            );
+        }
+
+        complement
+    }
+}
+
+impl<V, W> Weighted<V, W>
+where
+    W: num_traits::Num + Copy + PartialOrd,
+{
+    /// returns the outgoing edges of `vertex` sorted ascending by weight
+    ///
+    /// Edges of equal weight keep their order. Weights that can not be compared
+    /// to themselves (such as `NaN`) are sorted after all other weights.
+    pub fn neighbors_by_weight(&self, vertex: Handle) -> Vec<Connection<W>> {
+        let mut neighbors = self.edges[vertex.0].clone();
+        neighbors.sort_by(|a, b| compare_weights(&a.weight, &b.weight));
+        neighbors
+    }
+
+    /// Adds an edge from `from` to `to` with the given weight. If there already is an edge
+    /// from `from` to `to`, its weight is lowered to `weight` instead of adding a parallel edge.
+    ///
+    /// If there are multiple such edges already, only the first one is considered.
+    pub fn add_edge_keep_min(&mut self, from: Handle, to: Handle, weight: W) {
+        let existing = self.edges[from.0]
+            .iter_mut()
+            .find(|connection| connection.to == to);
+        match existing {
+            Some(connection) => {
+                if compare_weights(&weight, &connection.weight).is_lt() {
+                    connection.weight = weight;
+                }
+            }
+            None => self.add_edge(from, to, weight),
+        }
+    }
+
+    /// Returns an unweighted graph with the same vertices (and handles) that contains an edge
+    /// wherever this graph has an edge whose weight is on the side of `cutoff` given by `direction`.
+    ///
+    /// Parallel edges that pass the threshold are kept as parallel edges.
+    /// Weights that can not be compared with `cutoff` (such as `NaN`) never pass the threshold.
+    ///
+    /// ```
+    /// # use comtesse::weighted::{Threshold, Weighted};
+    /// let mut graph: Weighted<_, f64> = (0..3).collect();
+    /// graph.construct_edges_from(|&u, &v| (u != v).then_some(f64::from(u + v) / 3.0));
+    ///
+    /// assert_eq!(graph.threshold(0.5, Threshold::AtLeast).num_edges(), 4);
+    /// assert_eq!(graph.threshold(0.5, Threshold::AtMost).num_edges(), 2);
+    /// ```
+    pub fn threshold(&self, cutoff: W, direction: Threshold) -> Unweighted<V>
+    where
+        V: Clone,
+    {
+        let edges = self
+            .edges
+            .iter()
+            .map(|connections| {
+                connections
+                    .iter()
+                    .filter(|connection| match direction {
+                        Threshold::AtMost => connection.weight <= cutoff,
+                        Threshold::AtLeast => connection.weight >= cutoff,
+                    })
+                    .map(|connection| connection.to)
+                    .collect()
+            })
+            .collect();
+
+        Unweighted {
+            vertices: self.vertices.clone(),
+            edges,
+        }
+    }
+
+    /// Returns whether any edge has a weight less than zero
+    pub fn has_negative_weights(&self) -> bool {
+        self.edges
+            .iter()
+            .flatten()
+            .any(|connection| connection.weight < W::zero())
+    }
+
+    /// Multiplies the weight of every edge by `factor`
+    pub fn scale_weights(&mut self, factor: W) {
+        for connection in self.edges.iter_mut().flatten() {
+            connection.weight = connection.weight * factor;
+        }
+    }
+
+    /// Divides the weight of every edge by the largest weight, so that the largest weight becomes one.
+    ///
+    /// For non-negative weights this maps all weights into `[0, 1]`. If there are no edges or the
+    /// largest weight is not positive (e.g. because all weights are zero), the weights are left unchanged.
+    /// For integer weights the division rounds like integer division.
+    pub fn normalize_weights(&mut self) {
+        let max = self
+            .edges
+            .iter()
+            .flatten()
+            .map(|connection| connection.weight)
+            .max_by(compare_weights);
+
+        if let Some(max) = max.filter(|&max| max > W::zero()) {
+            for connection in self.edges.iter_mut().flatten() {
+                connection.weight = connection.weight / max;
+            }
+        }
+    }
+}
+
+/// Which edges are kept by [Weighted::threshold]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Threshold {
+    /// Keep the edges whose weight is less than or equal to the cutoff
+    AtMost,
+    /// Keep the edges whose weight is greater than or equal to the cutoff
+    AtLeast,
+}
+
+/// Compares two weights, ordering weights that are not comparable to themselves (like `NaN`) last
+pub(crate) fn compare_weights<W: PartialOrd>(a: &W, b: &W) -> Ordering {
+    match (a.partial_cmp(a).is_none(), b.partial_cmp(b).is_none()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+    }
 }
 
 impl<V, W> HasEdge for Weighted<V, W>
@@ -117,6 +562,10 @@ where
         let vertex = vertex.0;
         Box::new(self.edges[vertex].iter().map(|&Connection { to, .. }| to))
     }
+
+    fn neighbor_count(&self, vertex: Handle) -> usize {
+        self.edges[vertex.0].len()
+    }
 }
 
 impl<V, W> DumpGraphviz for Weighted<V, W>
@@ -125,36 +574,147 @@ where
     W: std::fmt::Debug + num_traits::Num + Copy,
 {
     fn dump(&self, output: &mut dyn Write) -> Result<(), std::fmt::Error> {
-        writeln!(output, "digraph {{")?;
-        for vertex in &self.vertices {
-            // TODO: vertex:? could inject stuff
-            let vertex_str = format!("{vertex:?}");
-            let vertex_str = make_safer(&vertex_str);
-            writeln!(output, "  \"{}\";", vertex_str)?;
-        }
+        self.write_dot(output, |from, Connection { to, weight }| {
+            if to.0 == from {
+                // otherwise the loop and its label are drawn on top of the vertex
+                format!("label=\"{weight:?}\", headport=n, tailport=n")
+            } else {
+                format!("label=\"{weight:?}\"")
+            }
+        })
+    }
+}
+
+impl<V, W> Weighted<V, W>
+where
+    V: std::fmt::Display,
+    W: std::fmt::Display + num_traits::Num + Copy,
+{
+    /// Returns the graph in the GraphML format, using the values of the vertices as their labels
+    /// and storing the weight of every edge as edge data.
+    ///
+    /// The weights are declared as `long` if `W` is an integer type, i.e. if dividing one by two gives zero,
+    /// and as `double` otherwise
+    pub fn to_graphml(&self) -> String {
+        let mut output = String::new();
+        self.dump_graphml(&mut output)
+            .expect("writing to a String does not fail");
+        output
+    }
 
-        for (from, edge) in self.edges.iter().enumerate() {
-            let from = &self.vertices[from];
-            let from = format!("{from:?}");
-            let from = make_safer(&from);
+    fn dump_graphml(&self, output: &mut dyn Write) -> std::fmt::Result {
+        let weight_type = if W::one() / (W::one() + W::one()) == W::zero() {
+            "long"
+        } else {
+            "double"
+        };
+        let weight = |connection: &Connection<W>| connection.weight.to_string();
+        self.write_graphml(output, Some((weight_type, &weight)))
+    }
+}
+
+impl<V, W> Weighted<V, W>
+where
+    V: std::fmt::Debug,
+    W: std::fmt::Debug + num_traits::Num + Copy,
+{
+    /// Returns the graph in the DOT format with the vertices and edges sorted by their labels,
+    /// so that the output does not depend on the order in which vertices and edges were added.
+    ///
+    /// Edges between the same vertices are ordered by the label of their weight.
+    pub fn to_dot_sorted(&self) -> String {
+        self.to_dot_sorted_with(|_, connection| format!("label=\"{:?}\"", connection.weight))
+    }
+}
 
-            for to in edge {
-                let (to, weight) = (&self.vertices[to.to.0], to.weight);
-                let to = format!("{to:?}");
-                let to = make_safer(&to);
+impl<V> Weighted<V, f64>
+where
+    V: std::fmt::Debug,
+{
+    /// Returns the graph in the DOT format, coloring each edge according to its weight.
+    ///
+    /// Edges with weight `low` are colored green and edges with weight `high` red,
+    /// with weights in between interpolated linearly. Weights outside of `[low, high]`
+    /// are clamped to the nearest end of the range and edges with a `NaN` weight are colored grey.
+    pub fn to_dot_heatmap(&self, low: f64, high: f64) -> String {
+        let mut output = String::new();
+        self.dump_heatmap(&mut output, low, high)
+            .expect("writing to a String does not fail");
+        output
+    }
 
-                writeln!(output, "  \"{from}\" -> \"{to}\" [label=\"{weight:?}\"];")?;
+    fn dump_heatmap(&self, output: &mut dyn Write, low: f64, high: f64) -> std::fmt::Result {
+        self.write_dot(output, |_, &Connection { weight, .. }| {
+            if weight.is_nan() {
+                return format!("label=\"{weight:?}\", color=\"#808080\"");
             }
-        }
-        writeln!(output, "}}")?;
+            let t = if high > low {
+                ((weight - low) / (high - low)).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let red = (255.0 * t).round() as u8;
+            let green = (255.0 * (1.0 - t)).round() as u8;
+            format!("label=\"{weight:?}\", color=\"#{red:02x}{green:02x}00\"")
+        })
+    }
+
+    /// Returns the graph in the DOT format, drawing each edge with a thickness according to its weight.
+    ///
+    /// The `penwidth` of an edge is proportional to its weight, with the heaviest edge of the graph
+    /// getting `max_pen`. Lighter edges whose width would fall below `min_pen`, including edges
+    /// with negative or `NaN` weights, are clamped to `min_pen`. If `min_pen` is larger than `max_pen`,
+    /// the two are swapped.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `min_pen` or `max_pen` is `NaN`
+    pub fn to_dot_weighted_thickness(&self, min_pen: f64, max_pen: f64) -> String {
+        let mut output = String::new();
+        self.dump_thickness(&mut output, min_pen, max_pen)
+            .expect("writing to a String does not fail");
+        output
+    }
+
+    fn dump_thickness(
+        &self,
+        output: &mut dyn Write,
+        min_pen: f64,
+        max_pen: f64,
+    ) -> std::fmt::Result {
+        assert!(
+            !min_pen.is_nan() && !max_pen.is_nan(),
+            "pen widths must not be NaN"
+        );
+        let (min_pen, max_pen) = if min_pen <= max_pen {
+            (min_pen, max_pen)
+        } else {
+            (max_pen, min_pen)
+        };
+        let heaviest = self
+            .edges
+            .iter()
+            .flatten()
+            .map(|connection| connection.weight)
+            .fold(0.0, f64::max);
 
-        Ok(())
+        self.write_dot(output, |_, &Connection { weight, .. }| {
+            let penwidth = max_pen * weight / heaviest;
+            // also covers NaN weights, an infinite heaviest weight and graphs without positive weights
+            let penwidth = if heaviest > 0.0 && !penwidth.is_nan() {
+                penwidth.clamp(min_pen, max_pen)
+            } else {
+                min_pen
+            };
+            format!("label=\"{weight:?}\", penwidth={penwidth:.2}")
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Weighted;
+    use super::{Threshold, Weighted};
+    use crate::{graph::Handle, DumpGraphviz, HasEdge, SizeMismatch};
 
     #[test]
     fn construct_weighted() {
@@ -198,4 +758,426 @@ mod tests {
             graph.get_vertex('b').unwrap(),
         );
     }
+
+    #[test]
+    fn neighbors_by_weight() {
+        let mut graph: Weighted<_, f32> = ('a'..='e').collect();
+        graph.construct_edges_from(|&from, &to| match (from, to) {
+            ('a', 'b') => Some(4.0),
+            ('a', 'c') => Some(f32::NAN),
+            ('a', 'd') => Some(-1.0),
+            ('a', 'e') => Some(2.5),
+            _ => None,
+        });
+        let a = graph.get_vertex('a').unwrap();
+
+        let sorted = graph.neighbors_by_weight(a);
+        let targets: Vec<Handle> = sorted.iter().map(|c| c.pointing_to()).collect();
+        let expected: Vec<Handle> = ['d', 'e', 'b', 'c']
+            .iter()
+            .map(|&v| graph.get_vertex(v).unwrap())
+            .collect();
+        assert_eq!(targets, expected);
+        assert!(sorted
+            .windows(2)
+            .all(|pair| pair[1].weight().is_nan() || pair[0].weight() <= pair[1].weight()));
+    }
+
+    #[test]
+    fn complement_with() {
+        let mut graph: Weighted<_, u32> = ('a'..='d').collect();
+        graph.construct_edges_from(|&from, &to| match (from, to) {
+            ('a', 'b') => Some(1),
+            ('a', 'c') => Some(2),
+            ('c', 'c') => Some(3),
+            _ => None,
+        });
+        let a = graph.get_vertex('a').unwrap();
+        graph.add_edge(a, graph.get_vertex('b').unwrap(), 7);
+
+        let complement = graph.complement_with(42);
+        assert_eq!(complement.size(), 4);
+        // 4 * 3 possible edges without self loops, 2 of which are in the graph
+        assert_eq!(complement.num_edges(), 10);
+        assert!((0..4).all(|v| complement
+            .neighbors(Handle(v))
+            .iter()
+            .all(|c| c.weight() == 42 && c.pointing_to() != Handle(v))));
+        assert_eq!(complement.get_edge(a, graph.get_vertex('b').unwrap()), None);
+        assert_eq!(
+            complement.get_edge(a, graph.get_vertex('d').unwrap()),
+            Some(42)
+        );
+    }
+
+    #[test]
+    fn heatmap() {
+        let mut graph: Weighted<_, f64> = ('a'..='c').collect();
+        graph.construct_edges_from(|&from, &to| match (from, to) {
+            ('a', 'b') => Some(0.0),
+            ('b', 'c') => Some(5.0),
+            ('c', 'a') => Some(20.0),
+            _ => None,
+        });
+
+        let dot = graph.to_dot_heatmap(0.0, 10.0);
+        assert!(dot.contains(r##""'a'" -> "'b'" [label="0.0", color="#00ff00"];"##));
+        assert!(dot.contains(r##""'b'" -> "'c'" [label="5.0", color="#808000"];"##));
+        // clamped to `high`
+        assert!(dot.contains(r##""'c'" -> "'a'" [label="20.0", color="#ff0000"];"##));
+
+        graph.add_edge(Handle(2), Handle(1), f64::NAN);
+        let dot = graph.to_dot_heatmap(0.0, 10.0);
+        assert!(dot.contains(r##""'c'" -> "'b'" [label="NaN", color="#808080"];"##));
+    }
+
+    #[test]
+    fn weighted_thickness() {
+        let mut graph: Weighted<_, f64> = ('a'..='d').collect();
+        graph.construct_edges_from(|&from, &to| match (from, to) {
+            ('a', 'b') => Some(2.0),
+            ('b', 'c') => Some(4.0),
+            ('c', 'd') => Some(8.0),
+            ('d', 'a') => Some(-1.0),
+            _ => None,
+        });
+
+        let dot = graph.to_dot_weighted_thickness(1.5, 6.0);
+        assert!(dot.contains(r#""'b'" -> "'c'" [label="4.0", penwidth=3.00];"#));
+        assert!(dot.contains(r#""'c'" -> "'d'" [label="8.0", penwidth=6.00];"#));
+        // clamped to `min_pen`
+        assert!(dot.contains(r#""'a'" -> "'b'" [label="2.0", penwidth=1.50];"#));
+        assert!(dot.contains(r#""'d'" -> "'a'" [label="-1.0", penwidth=1.50];"#));
+
+        // the widths are swapped and NaN weights get the smallest width
+        graph.add_edge(Handle(0), Handle(2), f64::NAN);
+        let dot = graph.to_dot_weighted_thickness(6.0, 1.5);
+        assert!(dot.contains(r#""'c'" -> "'d'" [label="8.0", penwidth=6.00];"#));
+        assert!(dot.contains(r#""'a'" -> "'c'" [label="NaN", penwidth=1.50];"#));
+    }
+
+    #[test]
+    fn construct_edges_by_key() {
+        let condition = |&from: &u32, &to: &u32| {
+            (from % 3 == to % 3 && from < to).then_some(f64::from(from * to))
+        };
+
+        let mut naive: Weighted<_, f64> = (0..30).collect();
+        naive.construct_edges_from(condition);
+
+        let mut bucketed: Weighted<_, f64> = (0..30).collect();
+        bucketed.construct_edges_by_key(|vertex| vertex % 3, condition);
+
+        assert_eq!(naive.num_edges(), bucketed.num_edges());
+        for vertex in 0..30 {
+            let naive: Vec<_> = naive
+                .neighbors(Handle(vertex))
+                .iter()
+                .map(|c| (c.pointing_to(), c.weight()))
+                .collect();
+            let bucketed: Vec<_> = bucketed
+                .neighbors(Handle(vertex))
+                .iter()
+                .map(|c| (c.pointing_to(), c.weight()))
+                .collect();
+            assert_eq!(naive, bucketed);
+        }
+    }
+
+    #[test]
+    fn split_weights() {
+        let mut graph: Weighted<_, u32> = (0..5).collect();
+        graph.construct_edges_from(|&from, &to| (from != to).then_some(from * 10 + to));
+        let edges = graph.num_edges();
+
+        let (unweighted, weights) = graph.split_weights();
+        assert_eq!(unweighted.num_edges(), edges);
+        for (from, weights) in weights.iter().enumerate() {
+            let neighbors = unweighted.neighbors(Handle(from));
+            assert_eq!(neighbors.len(), weights.len());
+            for (to, &weight) in neighbors.iter().zip(weights) {
+                assert_eq!(weight as usize, from * 10 + to.0);
+            }
+        }
+    }
+
+    #[test]
+    fn normalize_weights() {
+        let mut graph: Weighted<_, f64> = ('a'..='d').collect();
+        graph.construct_edges_from(|&from, &to| match (from, to) {
+            ('a', 'b') => Some(2.0),
+            ('b', 'c') => Some(8.0),
+            ('c', 'd') => Some(0.0),
+            _ => None,
+        });
+        graph.scale_weights(0.5);
+        assert_eq!(graph.get_edge(Handle(1), Handle(2)), Some(4.0));
+
+        graph.normalize_weights();
+        assert_eq!(graph.get_edge(Handle(0), Handle(1)), Some(0.25));
+        assert_eq!(graph.get_edge(Handle(1), Handle(2)), Some(1.0));
+        assert_eq!(graph.get_edge(Handle(2), Handle(3)), Some(0.0));
+
+        let mut zeros: Weighted<_, f64> = ('a'..='c').collect();
+        zeros.construct_edges_from(|&from, &to| (from < to).then_some(0.0));
+        zeros.normalize_weights();
+        assert!((0..3).all(|v| zeros.neighbors(Handle(v)).iter().all(|c| c.weight() == 0.0)));
+    }
+
+    #[test]
+    fn graphml() {
+        let mut graph: Weighted<_, f32> = ('a'..='f').collect();
+        graph.construct_edges_from(|&from, &to| match (from, to) {
+            ('a', 'b') => Some(9.0),
+            ('a', 'd') => Some(8.5),
+            ('b', 'c') => Some(1.0),
+            ('e', 'f') => Some(6.0),
+            _ => None,
+        });
+
+        let graphml = graph.to_graphml();
+        assert_eq!(graphml.matches("<node ").count(), 6);
+        assert_eq!(graphml.matches("<edge ").count(), 4);
+        assert_eq!(graphml.matches("</edge>").count(), 4);
+        assert!(graphml
+            .contains(r#"<edge source="n0" target="n3"><data key="weight">8.5</data></edge>"#));
+        assert!(graphml.contains(r#"attr.name="weight" attr.type="double""#));
+        assert!(graphml.trim_end().ends_with("</graphml>"));
+
+        let mut integral: Weighted<_, i32> = ('a'..='b').collect();
+        integral.add_edge(Handle(0), Handle(1), -3);
+        let graphml = integral.to_graphml();
+        assert!(graphml.contains(r#"attr.name="weight" attr.type="long""#));
+        assert!(graphml
+            .contains(r#"<edge source="n0" target="n1"><data key="weight">-3</data></edge>"#));
+    }
+
+    #[test]
+    fn retain_edges() {
+        let mut graph: Weighted<_, f64> = (0..4).collect();
+        graph.construct_edges_from(|&u, &v| (u != v).then_some(f64::from(u * 4 + v) / 10.0));
+
+        graph.retain_edges(|_, _, &weight| weight >= 1.0);
+        let mut survivors: Vec<_> = graph
+            .edges_with_values()
+            .map(|(&u, &v, weight)| (u, v, weight))
+            .collect();
+        survivors.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(
+            survivors,
+            [(2, 3, 1.1), (3, 0, 1.2), (3, 1, 1.3), (3, 2, 1.4)]
+        );
+        assert_eq!(graph.num_edges(), 4);
+    }
+
+    #[test]
+    fn dot_sorted() {
+        let mut first: Weighted<_, u32> = (0..4).collect();
+        first.construct_edges_from(|&u, &v| (u != v).then_some(u * v));
+        first.add_edge(Handle(1), Handle(2), 7);
+
+        let mut second: Weighted<_, u32> = (0..4).rev().collect();
+        second.add_edge(Handle(2), Handle(1), 7);
+        second.construct_edges_from(|&u, &v| (u != v).then_some(u * v));
+
+        assert_eq!(first.to_dot_sorted(), second.to_dot_sorted());
+        assert!(first
+            .to_dot_sorted()
+            .contains("  \"1\" -> \"2\" [label=\"2\"];\n  \"1\" -> \"2\" [label=\"7\"];\n"));
+    }
+
+    #[test]
+    fn neighbor_handles() {
+        let mut graph: Weighted<_, u32> = (1..=8).collect();
+        graph.construct_edges_from(|&u, &v| (u != v && v % u == 0).then_some(v / u));
+
+        for vertex in (0..graph.size()).map(Handle) {
+            let handles: Vec<_> = graph.neighbor_handles(vertex).collect();
+            let expected: Vec<_> = graph.neighbors(vertex).iter().map(|c| c.to).collect();
+            assert_eq!(handles, expected);
+        }
+        assert_eq!(graph.neighbor_handles(Handle(0)).count(), 7);
+    }
+
+    #[test]
+    fn add_edge_keep_min() {
+        let mut graph: Weighted<_, i32> = ('a'..='c').collect();
+        let (a, b, c) = (Handle(0), Handle(1), Handle(2));
+
+        graph.add_edge_keep_min(a, b, 5);
+        graph.add_edge_keep_min(a, b, 3);
+        graph.add_edge_keep_min(a, b, 4);
+        graph.add_edge_keep_min(b, a, 7);
+        graph.add_edge_keep_min(a, c, 1);
+
+        assert_eq!(graph.num_edges(), 3);
+        assert_eq!(graph.get_edge(a, b), Some(3));
+        assert_eq!(graph.get_edge(b, a), Some(7));
+        assert_eq!(graph.get_edge(a, c), Some(1));
+    }
+
+    #[test]
+    fn get_edge_ref() {
+        let mut graph: Weighted<_, u64> = (0..3).collect();
+        graph.construct_edges_from(|&u, &v| (u < v).then_some(10 * u + v));
+
+        let weight = graph.get_edge_ref(Handle(1), Handle(2)).unwrap();
+        assert_eq!(*weight, 12);
+        assert!(std::ptr::eq(weight, &graph.neighbors(Handle(1))[0].weight));
+        assert_eq!(graph.get_edge_ref(Handle(2), Handle(1)), None);
+    }
+
+    #[test]
+    fn get_connection() {
+        let mut graph: Weighted<_, i32> = (0..4).collect();
+        graph.construct_edges_from(|&u, &v| (u != v && (u + v) % 2 == 1).then_some(u - v));
+
+        let connection = graph.get_connection(Handle(3), Handle(0)).unwrap();
+        assert_eq!(connection.pointing_to(), Handle(0));
+        assert_eq!(connection.weight(), 3);
+        assert!(graph.get_connection(Handle(1), Handle(3)).is_none());
+    }
+
+    #[test]
+    fn threshold() {
+        let mut graph: Weighted<_, u32> = ('a'..='d').collect();
+        graph.construct_edges_from(|&from, &to| match (from, to) {
+            ('a', 'b') => Some(1),
+            ('b', 'c') => Some(5),
+            ('c', 'd') => Some(3),
+            ('d', 'a') => Some(8),
+            _ => None,
+        });
+
+        let light = graph.threshold(3, Threshold::AtMost);
+        assert_eq!(light.size(), 4);
+        assert_eq!(light.num_edges(), 2);
+        assert!(light.has_edge(Handle(0), Handle(1)));
+        assert!(light.has_edge(Handle(2), Handle(3)));
+
+        let heavy = graph.threshold(3, Threshold::AtLeast);
+        assert_eq!(heavy.num_edges(), 3);
+        assert!(!heavy.has_edge(Handle(0), Handle(1)));
+        assert!(heavy.has_edge(Handle(3), Handle(0)));
+    }
+
+    #[test]
+    fn construct_undirected_edges_from() {
+        let mut graph: Weighted<_, i32> = (1..=4).collect();
+        // this would give different weights to the two directions with `construct_edges_from`
+        graph.construct_undirected_edges_from(|&u, &v| (u % 2 == v % 2 || u == 1).then_some(u - v));
+
+        assert_eq!(graph.num_edges(), 12);
+        for from in (0..4).map(Handle) {
+            for to in (0..4).map(Handle) {
+                assert_eq!(graph.get_edge(from, to), graph.get_edge(to, from));
+            }
+        }
+        assert_eq!(graph.get_edge(Handle(0), Handle(2)), Some(-2));
+        assert_eq!(graph.get_edge(Handle(1), Handle(1)), Some(0));
+    }
+
+    #[test]
+    fn dump_self_loop() {
+        let mut graph: Weighted<_, u32> = ('a'..='b').collect();
+        graph.add_edge(Handle(0), Handle(0), 42);
+        graph.add_edge(Handle(0), Handle(1), 7);
+
+        let mut dot = String::new();
+        graph.dump(&mut dot).unwrap();
+        assert_eq!(dot.matches("\"'a'\" -> \"'a'\"").count(), 1);
+        assert!(dot.contains("\"'a'\" -> \"'a'\" [label=\"42\", headport=n, tailport=n];"));
+        assert!(dot.contains("\"'a'\" -> \"'b'\" [label=\"7\"];"));
+    }
+
+    #[test]
+    fn total_weight() {
+        let mut graph: Weighted<_, f32> = ('a'..='f').collect();
+        graph.construct_edges_from(|&from, &to| match (from, to) {
+            ('a', 'b') => Some(9.0),
+            ('a', 'd') => Some(8.0),
+            ('b', 'c') => Some(1.0),
+            ('b', 'e') => Some(3.0),
+            ('c', 'e') => Some(1.0),
+            ('c', 'd') => Some(5.0),
+            ('d', 'f') => Some(8.0),
+            ('e', 'f') => Some(6.0),
+            _ => None,
+        });
+        assert!((graph.total_weight() - 41.0).abs() < 1e-6);
+
+        let mut undirected: Weighted<_, u32> = (0..3).collect();
+        undirected.construct_undirected_edges_from(|&u, &v| (u != v).then_some(u + v));
+        assert_eq!(undirected.total_weight(), 2 * (1 + 2 + 3));
+        assert_eq!(Weighted::<(), u32>::new().total_weight(), 0);
+    }
+
+    #[test]
+    fn contract_edge() {
+        // a -1-> b, a -4-> c, b -2-> c, d -3-> a and two edges d -> b with weights 5 and 2
+        let mut graph: Weighted<_, u32> = ('a'..='d').collect();
+        graph.construct_edges_from(|&from, &to| match (from, to) {
+            ('a', 'b') => Some(1),
+            ('a', 'c') => Some(4),
+            ('b', 'c') => Some(2),
+            ('d', 'a') => Some(3),
+            ('d', 'b') => Some(5),
+            _ => None,
+        });
+        let (a, b, c, d) = (Handle(0), Handle(1), Handle(2), Handle(3));
+        graph.add_edge(d, b, 2);
+
+        let remap = graph.contract_edge(a, b, |x, y| x.min(y));
+        assert_eq!(graph.size(), 3);
+        assert_eq!(remap.map(b), remap.map(a));
+        let (a, c, d) = (
+            remap.map(a).unwrap(),
+            remap.map(c).unwrap(),
+            remap.map(d).unwrap(),
+        );
+        assert_eq!(*graph.vertex_value(a), 'a');
+        assert_eq!(graph.num_edges(), 2);
+        assert_eq!(graph.get_edge(a, c), Some(2));
+        assert_eq!(graph.get_edge(d, a), Some(2));
+        assert_eq!(graph.get_edge(a, a), None);
+    }
+
+    #[test]
+    fn quotient_graph() {
+        // a and b form the first group, c and d the second one
+        let mut graph: Weighted<_, u32> = ('a'..='d').collect();
+        graph.construct_edges_from(|&from, &to| match (from, to) {
+            ('a', 'b') => Some(1),
+            ('a', 'c') => Some(2),
+            ('b', 'c') => Some(3),
+            ('b', 'd') => Some(4),
+            ('c', 'd') => Some(5),
+            ('d', 'a') => Some(6),
+            _ => None,
+        });
+
+        let quotient = graph.quotient_graph(&[0, 0, 1, 1]).unwrap();
+        assert_eq!(quotient.size(), 2);
+        assert_eq!(*quotient.vertex_value(Handle(1)), 1);
+        assert_eq!(quotient.num_edges(), 2);
+        assert_eq!(quotient.get_edge(Handle(0), Handle(1)), Some(2 + 3 + 4));
+        assert_eq!(quotient.get_edge(Handle(1), Handle(0)), Some(6));
+
+        // group ids are compressed in order, even when they are huge
+        let sparse = graph
+            .quotient_graph(&[usize::MAX, usize::MAX, 7, 7])
+            .unwrap();
+        assert_eq!(sparse.size(), 2);
+        assert_eq!(*sparse.vertex_value(Handle(0)), 7);
+        assert_eq!(*sparse.vertex_value(Handle(1)), usize::MAX);
+        assert_eq!(sparse.get_edge(Handle(1), Handle(0)), Some(9));
+
+        assert_eq!(
+            graph.quotient_graph(&[0, 1]).err(),
+            Some(SizeMismatch {
+                expected: 4,
+                found: 2
+            })
+        );
+    }
 }